@@ -1,13 +1,20 @@
 use super::*;
 use futures_util::{SinkExt, StreamExt};
-use sc2_prost::{Request, Response};
-use tokio::net::{TcpStream, ToSocketAddrs};
+use sc2_prost::{Request, Response, Status, request::Request as RequestVar};
+use std::time::Duration;
+use tokio::{
+	net::{TcpStream, ToSocketAddrs},
+	time::{self, Instant},
+};
 use tokio_tungstenite::{WebSocketStream, client_async, tungstenite::Error as WsError};
 
 type WebSocket = WebSocketStream<TcpStream>;
 
+#[cfg(feature = "record")]
+use sc2_core::record::Recorder;
+
 #[doc(no_inline)]
-pub use sc2_core::{Error, Result};
+pub use sc2_core::{Error, Result, client::Backoff};
 
 /**
 Asynchronous client interface to connect and communicate with SC2 instance.
@@ -24,6 +31,13 @@ println!("{res:?}");
 #[derive(Debug)]
 pub struct Client {
 	ws: WebSocket,
+	pipeline: Pipeline,
+	read_timeout: Option<Duration>,
+	write_timeout: Option<Duration>,
+	status: Option<Status>,
+	check_status: bool,
+	#[cfg(feature = "record")]
+	recorder: Option<Recorder>,
 }
 /// Core methods
 impl Client {
@@ -31,7 +45,16 @@ impl Client {
 		stream.set_nodelay(true)?;
 		let req = format!("ws://{}/sc2api", stream.peer_addr()?);
 		let (ws, _) = client_async(req, stream).await?;
-		Ok(Self { ws })
+		Ok(Self {
+			ws,
+			pipeline: Default::default(),
+			read_timeout: None,
+			write_timeout: None,
+			status: None,
+			check_status: true,
+			#[cfg(feature = "record")]
+			recorder: None,
+		})
 	}
 
 	/**
@@ -53,6 +76,65 @@ impl Client {
 		Self::_connect(TcpStream::connect(addr).await?).await
 	}
 
+	/**
+	Repeatedly tries to connect to the given address until SC2 responds to `Ping`.
+
+	# Errors
+
+	Returns the last connection error if SC2 isn't ready before [`Backoff::timeout`].
+
+	# Examples
+	```no_run
+	use sc2_async_core::{Client, client::Backoff};
+
+	let mut client = Client::connect_retry("localhost:5000", &Backoff::default()).await?;
+	```
+	*/
+	pub async fn connect_retry(
+		addr: impl ToSocketAddrs + Clone,
+		backoff: &Backoff,
+	) -> Result<Self> {
+		Self::connect_retry_with(addr, backoff, async || Ok(())).await
+	}
+
+	/**
+	Same as [`connect_retry`](Self::connect_retry),
+	but calls `check` before each attempt, which can abort connecting with an error.
+	*/
+	pub async fn connect_retry_with<E: From<Error>>(
+		addr: impl ToSocketAddrs + Clone,
+		backoff: &Backoff,
+		mut check: impl AsyncFnMut() -> Result<(), E>,
+	) -> Result<Self, E> {
+		let deadline = Instant::now() + backoff.timeout;
+		let mut delays = backoff.delays();
+		loop {
+			check().await?;
+			let err = match Self::connect(addr.clone()).await {
+				Ok(mut client) => match client.ping().await {
+					Ok(()) => break Ok(client),
+					Err(e) => e,
+				},
+				Err(e) => e,
+			};
+			let delay = delays.next().unwrap_or_default();
+			if Instant::now() + delay > deadline {
+				break Err(err.into());
+			}
+			time::sleep(delay).await;
+		}
+	}
+
+	async fn ping(&mut self) -> Result {
+		let req = Request {
+			id: 0,
+			request: Some(RequestVar::Ping(Default::default())),
+		};
+		let res = self.request(req).await?;
+		convert_res(res, Kind::Ping)?;
+		Ok(())
+	}
+
 	/**
 	Sends given request and returns received response.
 
@@ -117,17 +199,34 @@ impl Client {
 	where
 		R: Into<Request> + ParseResponse,
 	{
+		if self.pipeline.check_idle().is_err() {
+			let pending = self.submit(request).await?;
+			return self.resolve(pending).await;
+		}
 		self.send(request).await?;
 		self.read::<R>().await
 	}
 
 	async fn _read(&mut self) -> Result<Response> {
-		let Some(msg) = self.ws.next().await else {
+		let Some(msg) = with_timeout(self.read_timeout, self.ws.next()).await? else {
 			return Err(WsError::AlreadyClosed.into());
 		};
-		res_from_msg(msg?)
+		let res = res_from_msg(msg?)?;
+		self.status = Some(res.status());
+		#[cfg(feature = "record")]
+		if let Some(recorder) = &mut self.recorder {
+			recorder.response(&res);
+		}
+		Ok(res)
 	}
 	pub async fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		// responses to cancelled pipelined requests come first
+		while self.pipeline.only_cancelled() {
+			self.flush().await?;
+			let res = self._read().await?;
+			self.pipeline.accept(res, 0)?;
+		}
+		self.pipeline.check_idle()?;
 		R::parse(self._read().await?)
 	}
 
@@ -137,15 +236,20 @@ impl Client {
 	}
 
 	async fn _write(&mut self, req: Request) -> Result {
+		self.check_status(&req)?;
+		#[cfg(feature = "record")]
+		if let Some(recorder) = &mut self.recorder {
+			recorder.request(&req);
+		}
 		let msg = req_into_msg(req);
-		Ok(self.ws.feed(msg).await?)
+		Ok(with_timeout(self.write_timeout, self.ws.feed(msg)).await??)
 	}
 	pub async fn write(&mut self, request: impl Into<Request>) -> Result {
 		self._write(request.into()).await
 	}
 
 	pub async fn flush(&mut self) -> Result {
-		Ok(self.ws.flush().await?)
+		Ok(with_timeout(self.write_timeout, self.ws.flush()).await??)
 	}
 
 	pub fn inner(&self) -> &WebSocket {
@@ -158,3 +262,178 @@ impl Client {
 		self.ws
 	}
 }
+async fn with_timeout<T>(timeout: Option<Duration>, fut: impl Future<Output = T>) -> Result<T> {
+	match timeout {
+		Some(timeout) => time::timeout(timeout, fut)
+			.await
+			.map_err(|_| Error::Timeout),
+		None => Ok(fut.await),
+	}
+}
+
+/// Status tracking
+impl Client {
+	/// Status of SC2 from the last received response. `None` if nothing was received yet
+	pub fn status(&self) -> Option<Status> {
+		self.status
+	}
+	/**
+	Enables checking requests against the last known status before sending.
+
+	When enabled, requests not allowed in the current status
+	fail with [`Error::InvalidState`] instead of being sent. Enabled by default.
+	*/
+	pub fn set_check_status(&mut self, value: bool) {
+		self.check_status = value;
+	}
+
+	fn check_status(&self, req: &Request) -> Result {
+		let Some(status) = self.status.filter(|_| self.check_status) else {
+			return Ok(());
+		};
+		let kind = req.kind();
+		if !kind.is_allowed_in(status) {
+			return Err(Error::InvalidState { kind, status });
+		}
+		Ok(())
+	}
+}
+
+/// Timeouts
+impl Client {
+	/**
+	Sets timeout for reading responses. `None` waits until response is received.
+
+	When the timeout elapses, methods return [`Error::Timeout`].
+	Response to the request will still arrive later,
+	so either read it or drop the client if SC2 seems to be frozen.
+	*/
+	pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+		self.read_timeout = timeout;
+	}
+	/**
+	Sets timeout for writing requests. `None` waits until request is sent.
+
+	Request may be partially sent when the timeout elapses,
+	so the connection shouldn't be used after that.
+	*/
+	pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+		self.write_timeout = timeout;
+	}
+	pub fn read_timeout(&self) -> Option<Duration> {
+		self.read_timeout
+	}
+	pub fn write_timeout(&self) -> Option<Duration> {
+		self.write_timeout
+	}
+
+	/**
+	Same as [`request`](Self::request), but gives up with [`Error::Timeout`]
+	if the response isn't received before the deadline.
+
+	# Examples
+	```no_run
+	use sc2_async_core::{Error, request::step};
+	use std::time::Duration;
+	use tokio::time::Instant;
+
+	let deadline = Instant::now() + Duration::from_secs(10);
+	match client.request_with_deadline(step(1), deadline).await {
+		Err(Error::Timeout) => println!("SC2 is frozen"),
+		res => println!("{res:?}"),
+	}
+	```
+	*/
+	pub async fn request_with_deadline<R>(
+		&mut self,
+		request: R,
+		deadline: Instant,
+	) -> Result<R::Output>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		time::timeout_at(deadline, self.request(request))
+			.await
+			.map_err(|_| Error::Timeout)?
+	}
+}
+
+/// Recording
+#[cfg(feature = "record")]
+impl Client {
+	/**
+	Starts recording all sent requests and received responses.
+	See [`record`](crate::record) module for details.
+	*/
+	pub fn record(&mut self, recorder: Recorder) {
+		self.recorder = Some(recorder);
+	}
+	/// Stops recording and returns the recorder if there was one
+	pub fn stop_recording(&mut self) -> Option<Recorder> {
+		self.recorder.take()
+	}
+	pub fn recorder(&self) -> Option<&Recorder> {
+		self.recorder.as_ref()
+	}
+}
+
+/// Pipelining
+impl Client {
+	/**
+	Writes given request with automatically assigned id and returns handle to its response.
+
+	Multiple requests can be submitted before reading any responses,
+	which saves a round-trip per request.
+	While submitted requests aren't resolved, [`request`](Self::request) goes through the pipeline too
+	and [`read`](Self::read) fails with [`Error::BadId`], since the next response belongs to them.
+	Dropping [`Pending`] handle without resolving cancels the request.
+
+	# Examples
+	```no_run
+	use sc2_async_core::request::{GameInfo, Ping, observation};
+
+	let obs = client.submit(observation()).await?;
+	let info = client.submit(GameInfo).await?;
+	let ping = client.submit(Ping).await?;
+
+	let info = client.resolve(info).await?;
+	let obs = client.resolve(obs).await?;
+	let ping = client.resolve(ping).await?;
+	```
+	*/
+	pub async fn submit<R>(&mut self, request: R) -> Result<Pending<R>>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		let mut req = request.into();
+		req.id = self.pipeline.next_id();
+		let id = req.id;
+		self._write(req).await?;
+		Ok(self.pipeline.push(id))
+	}
+
+	/**
+	Flushes submitted requests and waits for the response to the given one.
+
+	Responses to other pending requests received in the meantime are kept until resolved.
+
+	# Errors
+
+	In addition to [`request`](Self::request) errors,
+	returns [`Error::BadId`] when SC2 responds with unknown id
+	or skips the response to this request.
+	*/
+	pub async fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		let id = Pending::id(&pending);
+		if let Some(res) = self.pipeline.take(id)? {
+			return R::parse(res);
+		}
+		self.flush().await?;
+		loop {
+			let res = self._read().await?;
+			if let Some(res) = self.pipeline.accept(res, id)? {
+				break R::parse(res);
+			}
+		}
+	}
+}
@@ -0,0 +1,222 @@
+/*!
+Asynchronous version of [`sc2_core::launcher`].
+
+Launch configuration is shared with the blocking launcher,
+only the spawned process is managed by tokio.
+
+# Examples
+```no_run
+use sc2_async_core::{
+	client::Backoff,
+	launcher::{Launcher, launcher},
+};
+
+let mut instance = Launcher::new(&launcher())?.spawn()?;
+instance.ready(&Backoff::default()).await?;
+```
+*/
+
+use std::{
+	net::SocketAddr,
+	process::{ExitStatus, Stdio},
+	time::Duration,
+};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader, Lines},
+	process::{Child, ChildStderr, ChildStdout, Command},
+	time::{self, Instant},
+};
+
+#[doc(no_inline)]
+pub use sc2_core::launcher::{
+	DisplayMode, Error, GameVersion, InstalledVersion, LOG_CLOSE_TIMEOUT, LauncherBuilder, Limits,
+	LogCapture, LogEvent, LogWriter, Logs, OnDrop, RenderingLib, Result, Runner, Sandbox,
+	SandboxGuard, VERSIONS, VersionSelect, default_game_dir, find_game_dir, find_wine_game_dir,
+	from_windows_path, installed_versions, launcher, locate_game_dir, locate_wine_game_dir,
+	to_windows_path, wine_game_dir_candidates, wine_prefix,
+};
+
+#[cfg(feature = "client")]
+use crate::client::{Backoff, Client};
+
+#[derive(Debug)]
+pub struct Launcher {
+	cmd: Command,
+	addr: SocketAddr,
+	on_drop: OnDrop,
+	capture: LogCapture,
+	sandbox: Sandbox,
+}
+impl From<sc2_core::launcher::Launcher> for Launcher {
+	fn from(launcher: sc2_core::launcher::Launcher) -> Self {
+		let addr = launcher.addr();
+		let on_drop = launcher.on_drop();
+		let capture = launcher.capture();
+		let sandbox = launcher.sandbox().clone();
+		let cmd = Command::from(launcher.into_command());
+		Self {
+			cmd,
+			addr,
+			on_drop,
+			capture,
+			sandbox,
+		}
+	}
+}
+impl Launcher {
+	pub fn new(builder: &LauncherBuilder) -> Result<Self> {
+		Ok(builder.build()?.into())
+	}
+
+	/// Spawns the instance. Captured output is read by tasks on the current tokio runtime
+	pub fn spawn(&mut self) -> Result<Instance> {
+		let logs = if self.capture.is_enabled() {
+			Some(self.capture.start(self.addr)?)
+		} else {
+			None
+		};
+		let mut sandbox = self.sandbox.prepare()?;
+		let mut child = self.cmd.spawn()?;
+		sandbox.start(child.id());
+		if let Some(logs) = &logs {
+			if let Some(stdout) = child.stdout.take() {
+				tokio::spawn(capture(logs.writer(), stdout));
+			}
+			if let Some(stderr) = child.stderr.take() {
+				tokio::spawn(capture(logs.writer(), stderr));
+			}
+		}
+		Ok(Instance {
+			sandbox,
+			child,
+			addr: self.addr,
+			on_drop: self.on_drop,
+			logs,
+		})
+	}
+
+	pub fn command(&self) -> &Command {
+		&self.cmd
+	}
+	pub fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+
+	pub fn stdin(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stdin(cfg);
+		self
+	}
+	pub fn stdout(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stdout(cfg);
+		self
+	}
+	pub fn stderr(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stderr(cfg);
+		self
+	}
+}
+
+async fn capture(writer: LogWriter, output: impl AsyncRead + Unpin) {
+	let mut lines = BufReader::new(output).lines();
+	while let Ok(Some(line)) = lines.next_line().await {
+		writer.push(&line);
+	}
+}
+
+/**
+Launched SC2 instance.
+
+Unlike the blocking version, [`OnDrop::Wait`] doesn't wait for the process on drop,
+since it would block the runtime. Process is reaped in the background instead
+and its temp directory and watchdog are kept like with [`OnDrop::Keep`]
+unless it already exited, so await [`wait`](Self::wait) first to clean them up.
+*/
+#[derive(Debug)]
+pub struct Instance {
+	pub child: Child,
+	pub addr: SocketAddr,
+	pub on_drop: OnDrop,
+	/// Captured output if enabled with [`LauncherBuilder::capture_logs`] or [`LauncherBuilder::log_to_file`]
+	pub logs: Option<Logs>,
+	pub sandbox: SandboxGuard,
+}
+impl Instance {
+	pub async fn wait(&mut self) -> Result<ExitStatus> {
+		Ok(self.child.wait().await?)
+	}
+	/// Kills the instance together with its process group if it has one
+	pub async fn kill(&mut self) -> Result<()> {
+		self.sandbox.kill_group();
+		// process is reaped right after the kill
+		self.sandbox.stop();
+		Ok(self.child.kill().await?)
+	}
+
+	/// Takes piped stdout of the process as a stream of lines
+	pub fn stdout_lines(&mut self) -> Option<Lines<BufReader<ChildStdout>>> {
+		Some(BufReader::new(self.child.stdout.take()?).lines())
+	}
+	/// Takes piped stderr of the process as a stream of lines
+	pub fn stderr_lines(&mut self) -> Option<Lines<BufReader<ChildStderr>>> {
+		Some(BufReader::new(self.child.stderr.take()?).lines())
+	}
+
+	/// Checks if the instance process has exited
+	pub async fn check_exited(&mut self) -> Result<()> {
+		let Some(status) = self.sandbox.reap(|| self.child.try_wait())? else {
+			return Ok(());
+		};
+		let mut stderr = String::new();
+		if let Some(logs) = &self.logs {
+			let deadline = Instant::now() + LOG_CLOSE_TIMEOUT;
+			while !logs.is_closed() && Instant::now() < deadline {
+				time::sleep(Duration::from_millis(10)).await;
+			}
+			stderr = logs.lines().join("\n");
+		} else if let Some(mut pipe) = self.child.stderr.take() {
+			pipe.read_to_string(&mut stderr).await?;
+		}
+		Err(Error::Exited {
+			status,
+			stderr,
+			logs: self.logs.clone(),
+		})
+	}
+}
+#[cfg(feature = "client")]
+impl Instance {
+	/**
+	Waits until the instance is ready and connects to it.
+
+	Fails early if the process exits before accepting connection.
+	To get its error output in that case, launch it with piped stderr or captured logs.
+	*/
+	pub async fn connect(&mut self, backoff: &Backoff) -> Result<Client> {
+		let addr = self.addr;
+		Client::connect_retry_with(addr, backoff, async || self.check_exited().await).await
+	}
+	/// Waits until the instance accepts connections
+	pub async fn ready(&mut self, backoff: &Backoff) -> Result<()> {
+		self.connect(backoff).await.map(drop)
+	}
+}
+impl Drop for Instance {
+	fn drop(&mut self) {
+		match self.on_drop {
+			OnDrop::Keep => self.sandbox.keep(),
+			OnDrop::Wait => {
+				// guard is dropped as usual only if the process is already done
+				let child = &mut self.child;
+				if !matches!(self.sandbox.reap(|| child.try_wait()), Ok(Some(_))) {
+					self.sandbox.keep();
+				}
+			}
+			OnDrop::Kill => {
+				self.sandbox.kill_group();
+				let _ = self.child.start_kill();
+				// child is reaped in background after the kill
+				self.sandbox.stop();
+			}
+		}
+	}
+}
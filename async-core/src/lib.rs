@@ -25,5 +25,11 @@ pub use sc2_core::request;
 #[cfg(feature = "server")]
 pub mod server;
 
+#[cfg(feature = "proxy")]
+pub mod proxy;
+
 #[cfg(feature = "launcher")]
-pub use sc2_core::launcher;
+pub mod launcher;
+
+#[cfg(feature = "record")]
+pub mod record;
@@ -0,0 +1,89 @@
+/*!
+Transparent proxy between bot and SC2 with hooks to inspect traffic.
+
+Asynchronous version of [`sc2_core::proxy`].
+*/
+
+use super::*;
+use crate::server::{Server, ServerClient};
+use sc2_prost::{Request, Response};
+use tokio::time;
+
+#[doc(no_inline)]
+pub use sc2_core::proxy::{Error, ProxyHooks, Result, Verdict};
+
+/// Proxy between single bot and SC2. See [`sc2_core::proxy`] docs for details.
+#[derive(Debug)]
+pub struct Proxy<H> {
+	bot: ServerClient,
+	sc2: Client,
+	hooks: H,
+}
+impl<H: ProxyHooks> Proxy<H> {
+	/// Status checks of the client are disabled, so SC2 itself responds to invalid requests
+	pub fn new(bot: ServerClient, mut sc2: Client, hooks: H) -> Self {
+		sc2.set_check_status(false);
+		Self { bot, sc2, hooks }
+	}
+	/// Waits for the bot to connect to the given server
+	pub async fn accept(server: &Server, sc2: Client, hooks: H) -> Result<Self> {
+		let (bot, _) = server.accept().await?;
+		Ok(Self::new(bot, sc2, hooks))
+	}
+
+	/// Proxies messages until the bot disconnects or SC2 quits
+	pub async fn run(&mut self) -> Result {
+		loop {
+			let req = match self.bot.read().await {
+				Ok(req) => req,
+				Err(e) if e.is_disconnect() => break Ok(()),
+				Err(e) => break Err(e.into()),
+			};
+			let kind = req.kind();
+			if let Some(res) = self.forward(req).await? {
+				self.reply(kind, res).await?;
+			}
+			if kind == Kind::Quit {
+				break Ok(());
+			}
+		}
+	}
+
+	/// Passes the request to SC2 and returns its response if there is one
+	async fn forward(&mut self, req: Request) -> Result<Option<Response>> {
+		let req = match self.hooks.on_request(req) {
+			Verdict::Forward(req) => req,
+			Verdict::Delay(req, delay) => {
+				time::sleep(delay).await;
+				req
+			}
+			Verdict::Reply(res) => return Ok(Some(*res)),
+			Verdict::Drop => return Ok(None),
+		};
+		self.sc2.send(req).await?;
+		Ok(Some(self.sc2.read::<Request>().await?))
+	}
+
+	async fn reply(&mut self, kind: Kind, res: Response) -> Result {
+		let res = match self.hooks.on_response(kind, res) {
+			Verdict::Forward(res) => res,
+			Verdict::Reply(res) => *res,
+			Verdict::Delay(res, delay) => {
+				time::sleep(delay).await;
+				res
+			}
+			Verdict::Drop => return Ok(()),
+		};
+		Ok(self.bot.send(res).await?)
+	}
+
+	pub fn hooks(&self) -> &H {
+		&self.hooks
+	}
+	pub fn hooks_mut(&mut self) -> &mut H {
+		&mut self.hooks
+	}
+	pub fn into_parts(self) -> (ServerClient, Client, H) {
+		(self.bot, self.sc2, self.hooks)
+	}
+}
@@ -0,0 +1,64 @@
+/*!
+Recording of protocol traffic and its deterministic playback.
+
+Asynchronous version of [`sc2_core::record`].
+*/
+
+use super::*;
+use sc2_core::record as sync;
+use sc2_prost::Request;
+use std::path::Path;
+
+#[doc(no_inline)]
+pub use sync::{Entry, Mismatch, Recorder, read_entries};
+
+/// Asynchronous version of [`sc2_core::record::RecordedClient`]
+#[derive(Debug, Default)]
+pub struct RecordedClient(sync::RecordedClient);
+impl RecordedClient {
+	pub fn new(entries: Vec<Entry>) -> Self {
+		Self(sync::RecordedClient::new(entries))
+	}
+	/// Loads record from the file at the given path
+	pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+		sync::RecordedClient::open(path).map(Self)
+	}
+
+	pub async fn request<R>(&mut self, request: R) -> Result<R::Output>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		self.0.request(request)
+	}
+	pub async fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		self.0.read::<R>()
+	}
+	pub async fn send(&mut self, request: impl Into<Request>) -> Result {
+		self.0.send(request)
+	}
+	pub async fn write(&mut self, request: impl Into<Request>) -> Result {
+		self.0.write(request)
+	}
+	pub async fn flush(&mut self) -> Result {
+		self.0.flush()
+	}
+	pub async fn submit<R>(&mut self, request: R) -> Result<Pending<R>>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		self.0.submit(request)
+	}
+	pub async fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		self.0.resolve(pending)
+	}
+
+	pub fn inner(&self) -> &sync::RecordedClient {
+		&self.0
+	}
+	pub fn inner_mut(&mut self) -> &mut sync::RecordedClient {
+		&mut self.0
+	}
+	pub fn into_inner(self) -> sync::RecordedClient {
+		self.0
+	}
+}
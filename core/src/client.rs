@@ -1,11 +1,16 @@
 use super::*;
+use sc2_prost::Status;
 use std::{
-	io,
+	io, iter,
 	net::{SocketAddr, TcpStream, ToSocketAddrs},
-	time::Duration,
+	thread,
+	time::{Duration, Instant},
 };
 use tungstenite::{ClientHandshake, Error as WsError, HandshakeError};
 
+#[cfg(feature = "record")]
+use crate::record::Recorder;
+
 type WebSocket = tungstenite::WebSocket<TcpStream>;
 type HsError = HandshakeError<ClientHandshake<TcpStream>>;
 
@@ -23,9 +28,18 @@ pub enum Error {
 	/// Response [`Kind`] doesn't match request [`Kind`]
 	#[error("{0}")]
 	BadRes(#[from] BadResError),
+	/// Response id doesn't match any pending request
+	#[error("{0}")]
+	BadId(#[from] BadIdError),
 	/// Response contains some errors
 	#[error("{0}")]
 	Sc2(#[from] Sc2Error),
+	/// SC2 didn't respond in time
+	#[error("Timed out")]
+	Timeout,
+	/// Request can't be sent in the current status of SC2
+	#[error("{kind:?} request is not allowed in {status:?} status")]
+	InvalidState { kind: Kind, status: Status },
 }
 impl From<io::Error> for Error {
 	fn from(e: io::Error) -> Self {
@@ -38,8 +52,64 @@ impl From<HsError> for Error {
 	}
 }
 
+fn ws_error(e: WsError) -> Error {
+	match e {
+		WsError::Io(e)
+			if matches!(
+				e.kind(),
+				io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+			) =>
+		{
+			Error::Timeout
+		}
+		e => e.into(),
+	}
+}
+
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
 
+/**
+Exponential backoff for connection attempts.
+
+Freshly launched SC2 needs a few seconds to open its WebSocket server,
+so connecting right after launch should be retried.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Backoff {
+	/// Delay after the first failed attempt
+	pub initial: Duration,
+	/// Upper limit for delay between attempts
+	pub max: Duration,
+	/// Multiplier applied to delay after each attempt
+	pub factor: u32,
+	/// Total time after which connecting is considered failed
+	pub timeout: Duration,
+}
+impl Default for Backoff {
+	fn default() -> Self {
+		Self {
+			initial: Duration::from_millis(100),
+			max: Duration::from_secs(2),
+			factor: 2,
+			timeout: Duration::from_secs(60),
+		}
+	}
+}
+impl Backoff {
+	/// Infinite iterator of delays between attempts
+	pub fn delays(self) -> impl Iterator<Item = Duration> {
+		let Self {
+			initial,
+			max,
+			factor,
+			..
+		} = self;
+		iter::successors(Some(initial.min(max)), move |d| {
+			Some(d.checked_mul(factor).map_or(max, |d| d.min(max)))
+		})
+	}
+}
+
 /**
 Client interface to connect and communicate with SC2 instance.
 
@@ -55,6 +125,11 @@ println!("{res:?}");
 #[derive(Debug)]
 pub struct Client {
 	ws: WebSocket,
+	pipeline: Pipeline,
+	status: Option<Status>,
+	check_status: bool,
+	#[cfg(feature = "record")]
+	recorder: Option<Recorder>,
 }
 /// Core methods
 impl Client {
@@ -62,7 +137,14 @@ impl Client {
 		stream.set_nodelay(true)?;
 		let req = format!("ws://{}/sc2api", stream.peer_addr()?);
 		let (ws, _) = tungstenite::client(req, stream)?;
-		Ok(Self { ws })
+		Ok(Self {
+			ws,
+			pipeline: Default::default(),
+			status: None,
+			check_status: true,
+			#[cfg(feature = "record")]
+			recorder: None,
+		})
 	}
 
 	/**
@@ -87,6 +169,66 @@ impl Client {
 		Self::_connect(TcpStream::connect_timeout(addr, timeout)?)
 	}
 
+	/**
+	Repeatedly tries to connect to the given address until SC2 responds to `Ping`.
+
+	# Errors
+
+	Returns the last connection error if SC2 isn't ready before [`Backoff::timeout`].
+
+	# Examples
+	```no_run
+	use sc2_core::{Client, client::Backoff};
+
+	let mut client = Client::connect_retry("localhost:5000", &Backoff::default())?;
+	```
+	*/
+	pub fn connect_retry(addr: impl ToSocketAddrs, backoff: &Backoff) -> Result<Self> {
+		Self::connect_retry_with(addr, backoff, || Ok(()))
+	}
+
+	/**
+	Same as [`connect_retry`](Self::connect_retry),
+	but calls `check` before each attempt, which can abort connecting with an error.
+	*/
+	pub fn connect_retry_with<E: From<Error>>(
+		addr: impl ToSocketAddrs,
+		backoff: &Backoff,
+		mut check: impl FnMut() -> Result<(), E>,
+	) -> Result<Self, E> {
+		let addrs = addr
+			.to_socket_addrs()
+			.map_err(Error::from)?
+			.collect::<Vec<_>>();
+		let deadline = Instant::now() + backoff.timeout;
+		let mut delays = backoff.delays();
+		loop {
+			check()?;
+			let err = match Self::connect(&addrs[..]).and_then(|mut client| {
+				client.ping()?;
+				Ok(client)
+			}) {
+				Ok(client) => break Ok(client),
+				Err(e) => e,
+			};
+			let delay = delays.next().unwrap_or_default();
+			if Instant::now() + delay > deadline {
+				break Err(err.into());
+			}
+			thread::sleep(delay);
+		}
+	}
+
+	fn ping(&mut self) -> Result {
+		let req = Request {
+			id: 0,
+			request: Some(RequestVar::Ping(Default::default())),
+		};
+		let res = self.request(req)?;
+		convert_res(res, Kind::Ping)?;
+		Ok(())
+	}
+
 	/**
 	Sends given request and returns received response.
 
@@ -148,15 +290,32 @@ impl Client {
 	```
 	*/
 	pub fn request<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<R::Output> {
+		if self.pipeline.check_idle().is_err() {
+			let pending = self.submit(request)?;
+			return self.resolve(pending);
+		}
 		self.send(request)?;
 		self.read::<R>()
 	}
 
 	fn _read(&mut self) -> Result<Response> {
-		let msg = self.ws.read()?;
-		res_from_msg(msg)
+		let msg = self.ws.read().map_err(ws_error)?;
+		let res = res_from_msg(msg)?;
+		self.status = Some(res.status());
+		#[cfg(feature = "record")]
+		if let Some(recorder) = &mut self.recorder {
+			recorder.response(&res);
+		}
+		Ok(res)
 	}
 	pub fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		// responses to cancelled pipelined requests come first
+		while self.pipeline.only_cancelled() {
+			self.flush()?;
+			let res = self._read()?;
+			self.pipeline.accept(res, 0)?;
+		}
+		self.pipeline.check_idle()?;
 		R::parse(self._read()?)
 	}
 
@@ -166,15 +325,20 @@ impl Client {
 	}
 
 	fn _write(&mut self, req: Request) -> Result {
+		self.check_status(&req)?;
+		#[cfg(feature = "record")]
+		if let Some(recorder) = &mut self.recorder {
+			recorder.request(&req);
+		}
 		let msg = req_into_msg(req);
-		Ok(self.ws.write(msg)?)
+		self.ws.write(msg).map_err(ws_error)
 	}
 	pub fn write(&mut self, request: impl Into<Request>) -> Result {
 		self._write(request.into())
 	}
 
 	pub fn flush(&mut self) -> Result {
-		Ok(self.ws.flush()?)
+		self.ws.flush().map_err(ws_error)
 	}
 
 	pub fn inner(&self) -> &WebSocket {
@@ -187,3 +351,219 @@ impl Client {
 		self.ws
 	}
 }
+/// Timeouts
+impl Client {
+	/**
+	Sets timeout for reading responses. `None` blocks until response is received.
+
+	When the timeout elapses, methods return [`Error::Timeout`].
+	Response to the request will still arrive later,
+	so either read it or drop the client if SC2 seems to be frozen.
+	*/
+	pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result {
+		Ok(self.ws.get_ref().set_read_timeout(timeout)?)
+	}
+	/// Sets timeout for writing requests. `None` blocks until request is sent.
+	pub fn set_write_timeout(&mut self, timeout: Option<Duration>) -> Result {
+		Ok(self.ws.get_ref().set_write_timeout(timeout)?)
+	}
+	pub fn read_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.ws.get_ref().read_timeout()?)
+	}
+	pub fn write_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.ws.get_ref().write_timeout()?)
+	}
+
+	/**
+	Same as [`request`](Self::request), but gives up with [`Error::Timeout`]
+	if the response isn't received before the deadline.
+
+	Previously set timeouts are restored afterwards.
+
+	# Examples
+	```no_run
+	use sc2_core::{Error, request::step};
+	use std::time::{Duration, Instant};
+
+	let deadline = Instant::now() + Duration::from_secs(10);
+	match client.request_with_deadline(step(1), deadline) {
+		Err(Error::Timeout) => println!("SC2 is frozen"),
+		res => println!("{res:?}"),
+	}
+	```
+	*/
+	pub fn request_with_deadline<R>(&mut self, request: R, deadline: Instant) -> Result<R::Output>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		let read_timeout = self.read_timeout()?;
+		let write_timeout = self.write_timeout()?;
+		let res = self._request_with_deadline(request, deadline);
+		self.set_read_timeout(read_timeout)?;
+		self.set_write_timeout(write_timeout)?;
+		res
+	}
+	fn _request_with_deadline<R>(&mut self, request: R, deadline: Instant) -> Result<R::Output>
+	where
+		R: Into<Request> + ParseResponse,
+	{
+		let time_left = deadline
+			.checked_duration_since(Instant::now())
+			.filter(|t| !t.is_zero())
+			.ok_or(Error::Timeout)?;
+		self.set_write_timeout(Some(time_left))?;
+		self.set_read_timeout(Some(time_left))?;
+		// goes through the pipeline if there are pending requests
+		self.request(request)
+	}
+}
+
+/// Status tracking
+impl Client {
+	/// Status of SC2 from the last received response. `None` if nothing was received yet
+	pub fn status(&self) -> Option<Status> {
+		self.status
+	}
+	/**
+	Enables checking requests against the last known status before sending.
+
+	When enabled, requests not allowed in the current status
+	fail with [`Error::InvalidState`] instead of being sent. Enabled by default.
+	*/
+	pub fn set_check_status(&mut self, value: bool) {
+		self.check_status = value;
+	}
+
+	fn check_status(&self, req: &Request) -> Result {
+		let Some(status) = self.status.filter(|_| self.check_status) else {
+			return Ok(());
+		};
+		let kind = req.kind();
+		if !kind.is_allowed_in(status) {
+			return Err(Error::InvalidState { kind, status });
+		}
+		Ok(())
+	}
+}
+
+/// Recording
+#[cfg(feature = "record")]
+impl Client {
+	/**
+	Starts recording all sent requests and received responses.
+	See [`record`](crate::record) module for details.
+	*/
+	pub fn record(&mut self, recorder: Recorder) {
+		self.recorder = Some(recorder);
+	}
+	/// Stops recording and returns the recorder if there was one
+	pub fn stop_recording(&mut self) -> Option<Recorder> {
+		self.recorder.take()
+	}
+	pub fn recorder(&self) -> Option<&Recorder> {
+		self.recorder.as_ref()
+	}
+}
+
+/// Pipelining
+impl Client {
+	/**
+	Writes given request with automatically assigned id and returns handle to its response.
+
+	Multiple requests can be submitted before reading any responses,
+	which saves a round-trip per request.
+	While submitted requests aren't resolved, [`request`](Self::request) goes through the pipeline too
+	and [`read`](Self::read) fails with [`Error::BadId`], since the next response belongs to them.
+	Dropping [`Pending`] handle without resolving cancels the request.
+
+	# Examples
+	```no_run
+	use sc2_core::request::{GameInfo, Ping, observation};
+
+	let obs = client.submit(observation())?;
+	let info = client.submit(GameInfo)?;
+	let ping = client.submit(Ping)?;
+
+	let info = client.resolve(info)?;
+	let obs = client.resolve(obs)?;
+	let ping = client.resolve(ping)?;
+	```
+	*/
+	pub fn submit<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<Pending<R>> {
+		let mut req = request.into();
+		req.id = self.pipeline.next_id();
+		let id = req.id;
+		self._write(req)?;
+		Ok(self.pipeline.push(id))
+	}
+
+	/**
+	Flushes submitted requests and waits for the response to the given one.
+
+	Responses to other pending requests received in the meantime are kept until resolved.
+
+	# Errors
+
+	In addition to [`request`](Self::request) errors,
+	returns [`Error::BadId`] when SC2 responds with unknown id
+	or skips the response to this request.
+	*/
+	pub fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		let id = pending.id;
+		if let Some(res) = self.pipeline.take(id)? {
+			return R::parse(res);
+		}
+		self.flush()?;
+		loop {
+			let res = self._read()?;
+			if let Some(res) = self.pipeline.accept(res, id)? {
+				break R::parse(res);
+			}
+		}
+	}
+}
+
+/**
+Request interface shared by [`Client`] and recorded playback.
+
+Code generic over it can be run both against SC2 and against
+[`RecordedClient`](crate::record::RecordedClient).
+Methods are the same as the ones of [`Client`].
+*/
+pub trait Transport {
+	fn request<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<R::Output>;
+	fn read<R: ParseResponse>(&mut self) -> Result<R::Output>;
+	fn send(&mut self, request: impl Into<Request>) -> Result;
+	fn write(&mut self, request: impl Into<Request>) -> Result;
+	fn flush(&mut self) -> Result;
+	fn submit<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<Pending<R>>;
+	fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output>;
+	/// Status of SC2 from the last response
+	fn status(&self) -> Option<Status>;
+}
+impl Transport for Client {
+	fn request<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<R::Output> {
+		Client::request(self, request)
+	}
+	fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		Client::read::<R>(self)
+	}
+	fn send(&mut self, request: impl Into<Request>) -> Result {
+		Client::send(self, request)
+	}
+	fn write(&mut self, request: impl Into<Request>) -> Result {
+		Client::write(self, request)
+	}
+	fn flush(&mut self) -> Result {
+		Client::flush(self)
+	}
+	fn submit<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<Pending<R>> {
+		Client::submit(self, request)
+	}
+	fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		Client::resolve(self, pending)
+	}
+	fn status(&self) -> Option<Status> {
+		Client::status(self)
+	}
+}
@@ -1,6 +1,14 @@
 use super::*;
-use sc2_prost::Status;
-use std::fmt;
+use sc2_prost::{
+	Status, response_create_game, response_join_game, response_map_command, response_replay_info,
+	response_restart_game, response_save_map, response_start_replay,
+};
+use std::{
+	fmt,
+	hash::{Hash, Hasher},
+	marker::PhantomData,
+	sync::{Arc, Mutex, PoisonError},
+};
 
 /// Enum to identify kind of request/response
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -30,6 +38,30 @@ pub enum Kind {
 	Debug = 20,
 }
 
+impl Kind {
+	/// Checks if request of this kind can be sent when SC2 has the given status
+	pub fn is_allowed_in(self, status: Status) -> bool {
+		use Status::*;
+		match self {
+			Kind::None => false,
+			Kind::CreateGame | Kind::StartReplay => {
+				matches!(status, Launched | InGame | InReplay | Ended)
+			}
+			Kind::JoinGame => matches!(status, Launched | InitGame),
+			Kind::RestartGame => matches!(status, InGame | Ended),
+			Kind::LeaveGame => matches!(status, InGame | Ended),
+			Kind::QuickSave | Kind::QuickLoad => status == InGame,
+			Kind::GameInfo | Kind::Data | Kind::Step => matches!(status, InGame | InReplay),
+			Kind::Observation => matches!(status, InGame | InReplay | Ended),
+			Kind::Action | Kind::Query | Kind::MapCommand | Kind::Debug => status == InGame,
+			Kind::ObsAction => status == InReplay,
+			Kind::SaveReplay => matches!(status, InGame | InReplay | Ended),
+			Kind::SaveMap => matches!(status, Launched | InitGame),
+			Kind::Quit | Kind::Ping | Kind::AvailableMaps | Kind::ReplayInfo => status != Quit,
+		}
+	}
+}
+
 /// Extension trait to quickly get kind of request/response
 pub trait KindOf {
 	fn kind(&self) -> Kind;
@@ -92,13 +124,90 @@ impl KindOf for Response {
 	}
 }
 
+/// Typed error code of [`Sc2Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+	/// Response has no data, only error strings from [`Response::error`]
+	Protocol,
+	CreateGame(response_create_game::Error),
+	JoinGame(response_join_game::Error),
+	RestartGame(response_restart_game::Error),
+	StartReplay(response_start_replay::Error),
+	MapCommand(response_map_command::Error),
+	ReplayInfo(response_replay_info::Error),
+	SaveMap(response_save_map::Error),
+	/// Code which isn't known for the request kind
+	Unknown(i32),
+}
+impl ErrorCode {
+	/// Interprets error code from the response of the given kind
+	pub fn new(kind: Kind, code: i32) -> Self {
+		fn typed<E: TryFrom<i32>>(code: i32, f: impl FnOnce(E) -> ErrorCode) -> ErrorCode {
+			E::try_from(code).map_or(ErrorCode::Unknown(code), f)
+		}
+		match kind {
+			Kind::CreateGame => typed(code, Self::CreateGame),
+			Kind::JoinGame => typed(code, Self::JoinGame),
+			Kind::RestartGame => typed(code, Self::RestartGame),
+			Kind::StartReplay => typed(code, Self::StartReplay),
+			Kind::MapCommand => typed(code, Self::MapCommand),
+			Kind::ReplayInfo => typed(code, Self::ReplayInfo),
+			Kind::SaveMap => typed(code, Self::SaveMap),
+			_ => Self::Unknown(code),
+		}
+	}
+	/// Raw code as sent by SC2. It's `0` for protocol errors
+	pub fn code(&self) -> i32 {
+		match *self {
+			Self::Protocol => 0,
+			Self::CreateGame(e) => e as i32,
+			Self::JoinGame(e) => e as i32,
+			Self::RestartGame(e) => e as i32,
+			Self::StartReplay(e) => e as i32,
+			Self::MapCommand(e) => e as i32,
+			Self::ReplayInfo(e) => e as i32,
+			Self::SaveMap(e) => e as i32,
+			Self::Unknown(code) => code,
+		}
+	}
+	/**
+	Whether the same request may succeed if repeated.
+
+	True for failures to launch the game, network errors and replay download errors.
+	*/
+	pub fn is_retryable(&self) -> bool {
+		use response_join_game::Error as Join;
+		matches!(
+			self,
+			Self::JoinGame(Join::LaunchError | Join::NetworkError)
+				| Self::RestartGame(response_restart_game::Error::LaunchError)
+				| Self::StartReplay(response_start_replay::Error::LaunchError)
+				| Self::ReplayInfo(response_replay_info::Error::DownloadError)
+		)
+	}
+}
+impl fmt::Display for ErrorCode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Protocol => f.write_str("Protocol"),
+			Self::CreateGame(e) => fmt::Debug::fmt(e, f),
+			Self::JoinGame(e) => fmt::Debug::fmt(e, f),
+			Self::RestartGame(e) => fmt::Debug::fmt(e, f),
+			Self::StartReplay(e) => fmt::Debug::fmt(e, f),
+			Self::MapCommand(e) => fmt::Debug::fmt(e, f),
+			Self::ReplayInfo(e) => fmt::Debug::fmt(e, f),
+			Self::SaveMap(e) => fmt::Debug::fmt(e, f),
+			Self::Unknown(code) => write!(f, "Unknown({code})"),
+		}
+	}
+}
+
 /// Describes some error returned in the response
 #[derive(Debug, Error)]
-#[error("SC2 {kind:?} Error: `{err}` {desc}")]
+#[error("SC2 {kind:?} Error: `{code}` {desc}")]
 pub struct Sc2Error {
 	pub(crate) kind: Kind,
-	pub(crate) code: i32,
-	pub(crate) err: String,
+	pub(crate) code: ErrorCode,
 	pub(crate) desc: String,
 }
 impl KindOf for Sc2Error {
@@ -107,9 +216,42 @@ impl KindOf for Sc2Error {
 	}
 }
 impl Sc2Error {
+	/// Error with code from the response of the given kind
+	pub fn new(kind: Kind, code: i32, desc: String) -> Self {
+		Self {
+			kind,
+			code: ErrorCode::new(kind, code),
+			desc,
+		}
+	}
+	/// Error of the response without data
+	pub fn protocol(kind: Kind, errors: &[String]) -> Self {
+		Self {
+			kind,
+			code: ErrorCode::Protocol,
+			desc: errors.join("\n"),
+		}
+	}
+
+	/// Raw error code
 	pub fn code(&self) -> i32 {
+		self.code.code()
+	}
+	pub fn error_code(&self) -> ErrorCode {
 		self.code
 	}
+	/// Error details or protocol error strings
+	pub fn desc(&self) -> &str {
+		&self.desc
+	}
+	/// Whether the error isn't specific to the request kind
+	pub fn is_protocol(&self) -> bool {
+		self.code == ErrorCode::Protocol
+	}
+	/// See [`ErrorCode::is_retryable`]
+	pub fn is_retryable(&self) -> bool {
+		self.code.is_retryable()
+	}
 }
 
 /// Response type returned for all requests
@@ -125,12 +267,7 @@ impl TryFrom<Response> for Res {
 	fn try_from(res: Response) -> Result<Res, Sc2Error> {
 		let status = res.status();
 		let Some(data) = res.response else {
-			return Err(Sc2Error {
-				kind: Kind::None,
-				code: 0,
-				err: "Empty Response".into(),
-				desc: res.error.join("\n"),
-			});
+			return Err(Sc2Error::protocol(Kind::None, &res.error));
 		};
 		Ok(Res {
 			data,
@@ -212,6 +349,11 @@ impl From<PlayerId> for u32 {
 #[error("Bad response: `{0:?}`, expected `{1:?}`")]
 pub struct BadResError(pub Kind, pub Kind);
 
+/// Response id doesn't match the oldest pending request id
+#[derive(Debug, Error)]
+#[error("Bad response id: `{0}`, expected `{1:?}`")]
+pub struct BadIdError(pub u32, pub Option<u32>);
+
 pub trait ParseResponse {
 	type Output;
 	fn parse(res: Response) -> Result<Self::Output>;
@@ -259,9 +401,298 @@ impl<R: KindOf> KindOf for SetId<R> {
 	}
 }
 
+/**
+Handle to the response of pipelined request.
+
+Dropping it without resolving cancels the request, its response is discarded once received.
+*/
+#[must_use = "response must be resolved with the handle"]
+#[derive(Debug)]
+pub struct Pending<R> {
+	pub(crate) id: u32,
+	pub(crate) dropped: Arc<Mutex<Vec<u32>>>,
+	pub(crate) _req: PhantomData<fn() -> R>,
+}
+impl<R> Pending<R> {
+	pub fn id(&self) -> u32 {
+		self.id
+	}
+}
+impl<R> Drop for Pending<R> {
+	fn drop(&mut self) {
+		// no-op for already resolved requests
+		self.dropped
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.push(self.id);
+	}
+}
+impl<R> PartialEq for Pending<R> {
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
+	}
+}
+impl<R> Eq for Pending<R> {}
+impl<R> Hash for Pending<R> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.id.hash(state);
+	}
+}
+
+/**
+Converts path to the one understood by Windows programs running under Wine.
+
+Absolute paths are mapped to drive `Z:`, separators are replaced in relative ones.
+*/
+pub fn to_windows_path(path: &str) -> String {
+	let path = path.replace('/', "\\");
+	if path.starts_with('\\') {
+		format!("Z:{path}")
+	} else {
+		path
+	}
+}
+
+/**
+Human-readable dumps of protocol messages for debugging.
+
+Unlike `Debug` output, enums are shown by names, known game ids are resolved with [`IdNames`]
+and byte fields like [`ImageData`](sc2_prost::ImageData) or replay data
+are summarized by their size and checksum instead of printing all of the bytes.
+
+# Examples
+```no_run
+use sc2_core::common::{IdNames, dump};
+
+struct Marines;
+impl IdNames for Marines {
+	fn unit(&self, id: u32) -> Option<String> {
+		(id == 48).then(|| "Marine".into())
+	}
+}
+
+println!("{}", dump(&request));
+println!("{}", dump(&response).json().names(&Marines));
+```
+*/
+#[cfg(feature = "dump")]
+pub mod dump {
+	use serde::Serialize;
+	use serde_json::{Map, Value};
+	use std::fmt::{self, Write};
+
+	/// Resolves names of game ids. Unknown ids are shown as numbers
+	pub trait IdNames {
+		fn unit(&self, _id: u32) -> Option<String> {
+			None
+		}
+		fn ability(&self, _id: u32) -> Option<String> {
+			None
+		}
+		fn upgrade(&self, _id: u32) -> Option<String> {
+			None
+		}
+		fn buff(&self, _id: u32) -> Option<String> {
+			None
+		}
+		fn effect(&self, _id: u32) -> Option<String> {
+			None
+		}
+	}
+	/// Doesn't resolve any ids
+	impl IdNames for () {}
+
+	#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum Format {
+		/// Indented `key: value` lines
+		#[default]
+		Text,
+		/// Pretty-printed JSON
+		Json,
+	}
+
+	/// Message formatted with [`fmt::Display`]
+	pub struct Dump<'a, T> {
+		msg: &'a T,
+		names: &'a dyn IdNames,
+		format: Format,
+	}
+	impl<'a, T> Dump<'a, T> {
+		pub fn names(mut self, names: &'a dyn IdNames) -> Self {
+			self.names = names;
+			self
+		}
+		pub fn format(mut self, format: Format) -> Self {
+			self.format = format;
+			self
+		}
+		pub fn json(self) -> Self {
+			self.format(Format::Json)
+		}
+	}
+	impl<T: Serialize> fmt::Display for Dump<'_, T> {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			let mut value = serde_json::to_value(self.msg).map_err(|_| fmt::Error)?;
+			resolve(&mut value, None, self.names);
+			match self.format {
+				Format::Text => {
+					let mut s = String::new();
+					write_text(&mut s, &value, 0)?;
+					f.write_str(s.trim_start())
+				}
+				Format::Json => {
+					let s = serde_json::to_string_pretty(&value).map_err(|_| fmt::Error)?;
+					f.write_str(&s)
+				}
+			}
+		}
+	}
+
+	/// Dumps any protocol message, e.g. [`Request`](sc2_prost::Request) or [`Response`](sc2_prost::Response)
+	pub fn dump<T: Serialize>(msg: &T) -> Dump<'_, T> {
+		Dump {
+			msg,
+			names: &(),
+			format: Format::Text,
+		}
+	}
+
+	type Resolver = fn(&dyn IdNames, u32) -> Option<String>;
+	fn resolver(key: &str) -> Option<Resolver> {
+		Some(match key {
+			"unit_type" | "unit_id" | "unit_alias" | "tech_alias" | "tech_requirement" => {
+				|n, id| n.unit(id)
+			}
+			"ability_id" | "remaps_to_ability_id" => |n, id| n.ability(id),
+			"upgrade_id" | "upgrade_ids" => |n, id| n.upgrade(id),
+			"buff_id" | "buff_ids" => |n, id| n.buff(id),
+			"effect_id" => |n, id| n.effect(id),
+			_ => return None,
+		})
+	}
+	fn resolve(value: &mut Value, key: Option<&str>, names: &dyn IdNames) {
+		match value {
+			Value::Object(map) => {
+				if let Some(summary) = image_summary(map) {
+					*value = summary.into();
+					return;
+				}
+				for (key, value) in map.iter_mut() {
+					resolve(value, Some(key), names);
+				}
+			}
+			Value::Array(items) => {
+				if key.is_some_and(|key| BYTES_FIELDS.contains(&key))
+					&& items.iter().all(Value::is_number)
+				{
+					*value = bytes_summary(items).into();
+					return;
+				}
+				for item in items {
+					resolve(item, key, names);
+				}
+			}
+			Value::Number(num) => {
+				let Some(resolver) = key.and_then(resolver) else {
+					return;
+				};
+				let Some(id) = num.as_u64().and_then(|id| u32::try_from(id).ok()) else {
+					return;
+				};
+				if let Some(name) = resolver(names, id) {
+					*value = format!("{name} ({id})").into();
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Names of `bytes` fields: image, map, replay and saved replay data
+	const BYTES_FIELDS: &[&str] = &["data", "map_data", "replay_data"];
+
+	/// Summarizes bytes as their length and FNV-1a checksum
+	fn bytes_summary(data: &[Value]) -> String {
+		let checksum = data.iter().fold(0x811c9dc5_u32, |hash, byte| {
+			(hash ^ byte.as_u64().unwrap_or(0) as u32).wrapping_mul(0x01000193)
+		});
+		format!("{} bytes, checksum {checksum:08x}", data.len())
+	}
+	/// Summarizes `ImageData` as its size, bits per pixel and summary of the data
+	fn image_summary(map: &Map<String, Value>) -> Option<String> {
+		if map.len() != 3 {
+			return None;
+		}
+		let bpp = map.get("bits_per_pixel")?.as_u64()?;
+		let size = map.get("size")?;
+		let data = map.get("data")?.as_array()?;
+		let (x, y) = match size {
+			Value::Object(size) => (size.get("x")?.as_i64()?, size.get("y")?.as_i64()?),
+			_ => (0, 0),
+		};
+		Some(format!(
+			"ImageData {x}x{y}, {bpp} bpp, {}",
+			bytes_summary(data)
+		))
+	}
+
+	fn is_scalar(value: &Value) -> bool {
+		match value {
+			Value::Object(map) => map.is_empty(),
+			Value::Array(items) => items
+				.iter()
+				.all(|item| !item.is_object() && !item.is_array()),
+			_ => true,
+		}
+	}
+	fn write_scalar(s: &mut String, value: &Value) -> fmt::Result {
+		match value {
+			Value::String(v) if v.is_empty() => write!(s, "\"\""),
+			Value::String(v) => write!(s, "{v}"),
+			Value::Object(_) => write!(s, "{{}}"),
+			Value::Array(items) => {
+				write!(s, "[")?;
+				for (i, item) in items.iter().enumerate() {
+					if i > 0 {
+						write!(s, ", ")?;
+					}
+					write_scalar(s, item)?;
+				}
+				write!(s, "]")
+			}
+			v => write!(s, "{v}"),
+		}
+	}
+	fn write_text(s: &mut String, value: &Value, indent: usize) -> fmt::Result {
+		if is_scalar(value) {
+			write!(s, " ")?;
+			return write_scalar(s, value);
+		}
+		let pad = "  ".repeat(indent);
+		match value {
+			Value::Object(map) => {
+				for (key, value) in map {
+					write!(s, "\n{pad}{key}:")?;
+					write_text(s, value, indent + 1)?;
+				}
+			}
+			Value::Array(items) => {
+				for item in items {
+					write!(s, "\n{pad}-")?;
+					write_text(s, item, indent + 1)?;
+				}
+			}
+			_ => unreachable!("scalars are written above"),
+		}
+		Ok(())
+	}
+}
+#[cfg(feature = "dump")]
+pub use dump::{IdNames, dump};
+
 #[doc(hidden)]
 pub mod internal {
 	use prost::Message as _;
+	use std::collections::{HashMap, HashSet, VecDeque};
 	use tungstenite::Message;
 
 	use super::*;
@@ -286,4 +717,203 @@ pub mod internal {
 			e.into()
 		})
 	}
+
+	/// Keeps track of pipelined requests and matches responses to them by id
+	#[derive(Debug, Default)]
+	pub struct Pipeline {
+		last_id: u32,
+		pending: VecDeque<u32>,
+		received: HashMap<u32, Response>,
+		/// Pending requests which responses are discarded
+		cancelled: HashSet<u32>,
+		/// Ids of dropped [`Pending`] handles
+		dropped: Arc<Mutex<Vec<u32>>>,
+	}
+	impl Pipeline {
+		pub fn next_id(&self) -> u32 {
+			self.last_id.wrapping_add(1).max(1)
+		}
+		/// Registers id of the sent request
+		pub fn push<R>(&mut self, id: u32) -> Pending<R> {
+			self.last_id = id;
+			self.pending.push_back(id);
+			Pending {
+				id,
+				dropped: self.dropped.clone(),
+				_req: PhantomData,
+			}
+		}
+		/// Cancels requests which handles were dropped
+		fn sync(&mut self) {
+			let dropped =
+				std::mem::take(&mut *self.dropped.lock().unwrap_or_else(PoisonError::into_inner));
+			for id in dropped {
+				if self.received.remove(&id).is_none() && self.pending.contains(&id) {
+					self.cancelled.insert(id);
+				}
+			}
+		}
+		/// Fails if there are pipelined requests still waiting for responses,
+		/// because the next response read from the socket belongs to them
+		pub fn check_idle(&mut self) -> Result {
+			self.sync();
+			match self.pending.front() {
+				Some(&id) => Err(BadIdError(0, Some(id)).into()),
+				None => Ok(()),
+			}
+		}
+		/// Whether all requests waiting for responses are cancelled,
+		/// so their responses can be read and [accepted](Self::accept) to get idle
+		pub fn only_cancelled(&mut self) -> bool {
+			self.sync();
+			!self.pending.is_empty() && self.pending.iter().all(|id| self.cancelled.contains(id))
+		}
+		/// Returns already received response if there is one
+		pub fn take(&mut self, id: u32) -> Result<Option<Response>> {
+			self.sync();
+			if let Some(res) = self.received.remove(&id) {
+				return Ok(Some(res));
+			}
+			if !self.pending.contains(&id) || self.cancelled.contains(&id) {
+				return Err(BadIdError(id, self.pending.front().copied()).into());
+			}
+			Ok(None)
+		}
+		/**
+		Matches response to the pending request with its id.
+		Returns it back if it has the requested id, otherwise stores it for later.
+
+		Responses come in order of requests, so requests sent before the matched one
+		won't get any and are dropped. Responses with unknown ids or to cancelled requests are discarded.
+		*/
+		pub fn accept(&mut self, res: Response, id: u32) -> Result<Option<Response>> {
+			self.sync();
+			let Some(pos) = self.pending.iter().position(|&p| p == res.id) else {
+				return Err(BadIdError(res.id, self.pending.front().copied()).into());
+			};
+			let mut stale = false;
+			for p in self.pending.drain(..pos) {
+				self.cancelled.remove(&p);
+				stale |= p == id;
+			}
+			self.pending.pop_front();
+			if res.id == id {
+				return Ok(Some(res));
+			}
+			let expected = Some(res.id);
+			if !self.cancelled.remove(&res.id) {
+				self.received.insert(res.id, res);
+			}
+			if stale {
+				return Err(BadIdError(id, expected).into());
+			}
+			Ok(None)
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+
+		fn res(id: u32) -> Response {
+			Response {
+				id,
+				..Default::default()
+			}
+		}
+		fn push(pipeline: &mut Pipeline) -> (u32, Pending<()>) {
+			let pending = pipeline.push(pipeline.next_id());
+			(pending.id, pending)
+		}
+
+		#[test]
+		fn ids() {
+			let mut pipeline = Pipeline::default();
+			assert_eq!(push(&mut pipeline).0, 1);
+			assert_eq!(push(&mut pipeline).0, 2);
+			pipeline.last_id = u32::MAX;
+			assert_eq!(pipeline.next_id(), 1);
+		}
+		#[test]
+		fn in_order() {
+			let mut pipeline = Pipeline::default();
+			let ((a, _a), (b, _b)) = (push(&mut pipeline), push(&mut pipeline));
+			assert!(pipeline.check_idle().is_err());
+			assert!(pipeline.take(a).unwrap().is_none());
+			assert_eq!(pipeline.accept(res(a), a).unwrap().unwrap().id, a);
+			assert!(pipeline.take(b).unwrap().is_none());
+			assert_eq!(pipeline.accept(res(b), b).unwrap().unwrap().id, b);
+			assert!(pipeline.check_idle().is_ok());
+		}
+		#[test]
+		fn out_of_order_resolve() {
+			let mut pipeline = Pipeline::default();
+			let ((a, _a), (b, _b)) = (push(&mut pipeline), push(&mut pipeline));
+			assert!(pipeline.take(b).unwrap().is_none());
+			assert!(pipeline.accept(res(a), b).unwrap().is_none());
+			assert_eq!(pipeline.accept(res(b), b).unwrap().unwrap().id, b);
+			assert_eq!(pipeline.take(a).unwrap().unwrap().id, a);
+			assert!(pipeline.check_idle().is_ok());
+		}
+		#[test]
+		fn unknown_id() {
+			let mut pipeline = Pipeline::default();
+			let (a, _a) = push(&mut pipeline);
+			assert!(pipeline.take(a + 1).is_err());
+			assert!(pipeline.accept(res(a + 1), a).is_err());
+			// pending request is still tracked after unknown response
+			assert_eq!(pipeline.accept(res(a), a).unwrap().unwrap().id, a);
+		}
+		#[test]
+		fn double_resolve() {
+			let mut pipeline = Pipeline::default();
+			let (a, _a) = push(&mut pipeline);
+			assert!(pipeline.accept(res(a), a).unwrap().is_some());
+			assert!(pipeline.take(a).is_err());
+		}
+		#[test]
+		fn skipped_response() {
+			let mut pipeline = Pipeline::default();
+			let ((a, _a), (b, _b), (c, _c)) = (
+				push(&mut pipeline),
+				push(&mut pipeline),
+				push(&mut pipeline),
+			);
+			// response to `a` never came
+			assert!(pipeline.accept(res(b), a).is_err());
+			assert!(pipeline.take(a).is_err());
+			assert_eq!(pipeline.take(b).unwrap().unwrap().id, b);
+			assert_eq!(pipeline.accept(res(c), c).unwrap().unwrap().id, c);
+			assert!(pipeline.check_idle().is_ok());
+		}
+		#[test]
+		fn dropped_pending() {
+			let mut pipeline = Pipeline::default();
+			let (a, pending_a) = push(&mut pipeline);
+			let (b, _b) = push(&mut pipeline);
+			drop(pending_a);
+			assert!(!pipeline.only_cancelled());
+			assert!(pipeline.take(a).is_err());
+			// response to the cancelled request is discarded
+			assert!(pipeline.accept(res(a), b).unwrap().is_none());
+			assert!(pipeline.received.is_empty());
+			assert_eq!(pipeline.accept(res(b), b).unwrap().unwrap().id, b);
+			assert!(pipeline.check_idle().is_ok());
+		}
+		#[test]
+		fn dropped_received() {
+			let mut pipeline = Pipeline::default();
+			let ((a, pending_a), (b, pending_b)) = (push(&mut pipeline), push(&mut pipeline));
+			// `a` is received while resolving `b`
+			assert!(pipeline.accept(res(a), b).unwrap().is_none());
+			drop(pending_a);
+			assert!(!pipeline.only_cancelled());
+			assert!(pipeline.take(a).is_err());
+			assert!(pipeline.received.is_empty());
+			drop(pending_b);
+			assert!(pipeline.only_cancelled());
+			assert!(pipeline.accept(res(b), 0).unwrap().is_none());
+			assert!(pipeline.check_idle().is_ok());
+		}
+	}
 }
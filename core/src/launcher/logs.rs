@@ -0,0 +1,292 @@
+use super::*;
+use std::{
+	collections::VecDeque,
+	fs::File,
+	io::{BufRead, BufReader, Write},
+	sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+/// How long to wait for remaining output after the instance has exited
+pub const LOG_CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Notable event parsed from SC2 output
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogEvent {
+	/// `Version: B75689 (SC2.4.10)`
+	Version(String),
+	/// `Startup Phase 3 complete. Ready for commands.`
+	StartupPhase(u32),
+	/// `Listening on: 127.0.0.1:5000`
+	Listening(u16),
+	/// Line reporting crash or fatal error
+	Crash(String),
+}
+impl LogEvent {
+	/**
+	Parses single line of SC2 output.
+
+	Crashes are detected heuristically by words like `crash`, `fatal` or `exception`.
+	*/
+	pub fn parse(line: &str) -> Option<Self> {
+		let line = line.trim();
+		if let Some(rest) = line.strip_prefix("Version:") {
+			return Some(Self::Version(rest.trim().into()));
+		}
+		if let Some(rest) = line.strip_prefix("Startup Phase ") {
+			let phase = rest.split_whitespace().next()?.parse().ok()?;
+			return Some(Self::StartupPhase(phase));
+		}
+		if let Some(rest) = line.strip_prefix("Listening on:") {
+			let addr = rest.split_whitespace().next()?;
+			return Some(Self::Listening(addr.rsplit(':').next()?.parse().ok()?));
+		}
+		let lower = line.to_ascii_lowercase();
+		CRASH_MARKERS
+			.iter()
+			.any(|m| lower.contains(m))
+			.then(|| Self::Crash(line.into()))
+	}
+}
+const CRASH_MARKERS: &[&str] = &["crash", "fatal", "exception", "segmentation fault"];
+
+/// What to capture from instance output. See [`LauncherBuilder::capture_logs`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogCapture {
+	/// Number of last lines kept in memory
+	pub lines: usize,
+	/// Whether to write whole output to `sc2.log` in a temp dir of the instance
+	pub file: bool,
+}
+impl LogCapture {
+	pub fn is_enabled(&self) -> bool {
+		self.lines > 0 || self.file
+	}
+
+	/// Creates log storage for the instance listening on the given address
+	pub fn start(&self, addr: SocketAddr) -> io::Result<Logs> {
+		let path = if self.file {
+			let dir = instance_dir(addr);
+			fs::create_dir_all(&dir)?;
+			Some(dir.join("sc2.log"))
+		} else {
+			None
+		};
+		let file = path.as_ref().map(File::create).transpose()?;
+		Ok(Logs {
+			shared: Arc::new(Shared {
+				state: Mutex::new(State {
+					lines: VecDeque::with_capacity(self.lines),
+					capacity: self.lines,
+					events: vec![],
+					file,
+					open: 0,
+				}),
+				closed: Condvar::new(),
+			}),
+			path: path.map(Into::into),
+		})
+	}
+}
+
+/**
+Output captured from SC2 instance.
+
+Handle is cheap to clone and is updated in the background while the instance runs.
+*/
+#[derive(Debug, Clone)]
+pub struct Logs {
+	shared: Arc<Shared>,
+	path: Option<Arc<Path>>,
+}
+#[derive(Debug)]
+struct Shared {
+	state: Mutex<State>,
+	closed: Condvar,
+}
+#[derive(Debug)]
+struct State {
+	lines: VecDeque<String>,
+	capacity: usize,
+	events: Vec<LogEvent>,
+	file: Option<File>,
+	/// Number of streams still being read
+	open: usize,
+}
+impl Logs {
+	fn state(&self) -> MutexGuard<'_, State> {
+		self.shared
+			.state
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Path to the log file if it's written
+	pub fn path(&self) -> Option<&Path> {
+		self.path.as_deref()
+	}
+	/// Last captured lines of stdout and stderr
+	pub fn lines(&self) -> Vec<String> {
+		self.state().lines.iter().cloned().collect()
+	}
+	/// All events parsed so far
+	pub fn events(&self) -> Vec<LogEvent> {
+		self.state().events.clone()
+	}
+	/// Last completed startup phase. SC2 is ready for commands after the phase 3
+	pub fn startup_phase(&self) -> Option<u32> {
+		self.state().events.iter().rev().find_map(|e| match e {
+			LogEvent::StartupPhase(phase) => Some(*phase),
+			_ => None,
+		})
+	}
+	/// Port reported by SC2 as listening one
+	pub fn listening_port(&self) -> Option<u16> {
+		self.state().events.iter().find_map(|e| match e {
+			LogEvent::Listening(port) => Some(*port),
+			_ => None,
+		})
+	}
+	/// Lines reporting crashes
+	pub fn crashes(&self) -> Vec<String> {
+		self.state()
+			.events
+			.iter()
+			.filter_map(|e| match e {
+				LogEvent::Crash(line) => Some(line.clone()),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Opens new stream of lines. It's closed when the writer drops
+	pub fn writer(&self) -> LogWriter {
+		self.state().open += 1;
+		LogWriter(self.clone())
+	}
+	/// Reads lines from the given output in a background thread
+	pub fn capture(&self, output: impl io::Read + Send + 'static) -> JoinHandle<()> {
+		let writer = self.writer();
+		thread::spawn(move || {
+			for line in BufReader::new(output).lines() {
+				let Ok(line) = line else {
+					break;
+				};
+				writer.push(&line);
+			}
+		})
+	}
+
+	/// Whether all streams reached the end
+	pub fn is_closed(&self) -> bool {
+		self.state().open == 0
+	}
+	/// Waits until all streams reach the end. Returns `false` on timeout
+	pub fn wait_closed(&self, timeout: Duration) -> bool {
+		let deadline = Instant::now() + timeout;
+		let mut state = self.state();
+		while state.open > 0 {
+			let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+				return false;
+			};
+			state = self
+				.shared
+				.closed
+				.wait_timeout(state, left)
+				.unwrap_or_else(PoisonError::into_inner)
+				.0;
+		}
+		true
+	}
+
+	/// Summary of the log used in [`Error::Exited`] message
+	pub(super) fn summary(&self) -> String {
+		let mut s = match self.startup_phase() {
+			Some(phase) => format!(" after startup phase {phase}"),
+			None => " before startup".into(),
+		};
+		for line in self.crashes() {
+			s += "\n";
+			s += &line;
+		}
+		if let Some(path) = self.path() {
+			s += &format!("\nFull log: {}", path.display());
+		}
+		s
+	}
+}
+
+/// Stream of lines going into [`Logs`]
+#[derive(Debug)]
+pub struct LogWriter(Logs);
+impl LogWriter {
+	pub fn push(&self, line: &str) {
+		let mut state = self.0.state();
+		if let Some(file) = &mut state.file {
+			let _ = writeln!(file, "{line}");
+		}
+		if let Some(event) = LogEvent::parse(line) {
+			state.events.push(event);
+		}
+		if state.capacity > 0 {
+			if state.lines.len() == state.capacity {
+				state.lines.pop_front();
+			}
+			state.lines.push_back(line.into());
+		}
+	}
+}
+impl Drop for LogWriter {
+	fn drop(&mut self) {
+		self.0.state().open -= 1;
+		self.0.shared.closed.notify_all();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_events() {
+		assert_eq!(
+			LogEvent::parse("Version: B75689 (SC2.4.10)\r\n"),
+			Some(LogEvent::Version("B75689 (SC2.4.10)".into()))
+		);
+		assert_eq!(
+			LogEvent::parse("Startup Phase 3 complete. Ready for commands."),
+			Some(LogEvent::StartupPhase(3))
+		);
+		assert_eq!(
+			LogEvent::parse("Listening on: 127.0.0.1:5000"),
+			Some(LogEvent::Listening(5000))
+		);
+		assert_eq!(
+			LogEvent::parse("Listening on: [::1]:8168 (ipv6)"),
+			Some(LogEvent::Listening(8168))
+		);
+	}
+	#[test]
+	fn parse_crashes() {
+		for line in [
+			"Game has crashed",
+			"FATAL ERROR: out of memory",
+			"Unhandled exception at 0x0",
+			"Segmentation fault (core dumped)",
+		] {
+			assert_eq!(LogEvent::parse(line), Some(LogEvent::Crash(line.into())));
+		}
+	}
+	#[test]
+	fn parse_other() {
+		for line in [
+			"",
+			"Startup Phase x complete",
+			"Listening on: 127.0.0.1:port",
+			"Creating stub renderer...",
+		] {
+			assert_eq!(LogEvent::parse(line), None);
+		}
+	}
+}
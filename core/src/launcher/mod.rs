@@ -0,0 +1,750 @@
+use std::ffi::{OsStr, OsString};
+use std::io::Read;
+use std::net::{Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::{env, fmt, fs, io};
+
+use sc2_prost::ResponseReplayInfo;
+use thiserror::Error;
+
+#[cfg(feature = "client")]
+use crate::client::{self, Backoff, Client};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Addr(SocketAddr);
+impl Default for Addr {
+	fn default() -> Self {
+		Self((Ipv6Addr::LOCALHOST, 5000).into())
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("OS error: {0}")]
+	Os(#[from] io::Error),
+	/// None of the checked locations contains the game
+	#[error(
+		"Can't locate game directory. Please set it explicitly.{}",
+		rejected_list(.0)
+	)]
+	NoGameDir(Vec<RejectedDir>),
+	#[error("No game versions found")]
+	NoVersions,
+	#[error("Game version with base build {0} is not installed")]
+	NotInstalled(u32),
+	#[error("Game version with base build at least {required} is required, latest is {latest}")]
+	TooOld { required: u32, latest: u32 },
+	/// Instance exited before accepting connection
+	#[error(
+		"SC2 exited early with {status}{}\n{stderr}",
+		.logs.as_ref().map(Logs::summary).unwrap_or_default()
+	)]
+	Exited {
+		status: ExitStatus,
+		/// Error output, or last captured lines if the output was [captured](LauncherBuilder::capture_logs)
+		stderr: String,
+		logs: Option<Logs>,
+	},
+	#[cfg(feature = "client")]
+	#[error("Client error: {0}")]
+	Client(#[from] client::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Game directory candidate rejected during auto detection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RejectedDir {
+	pub path: PathBuf,
+	pub reason: &'static str,
+}
+impl fmt::Display for RejectedDir {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "`{}`: {}", self.path.display(), self.reason)
+	}
+}
+fn rejected_list(rejected: &[RejectedDir]) -> String {
+	rejected.iter().map(|r| format!("\n- {r}")).collect()
+}
+
+mod logs;
+mod sandbox;
+mod versions;
+mod wine;
+pub use logs::*;
+pub use sandbox::*;
+pub use versions::*;
+pub use wine::*;
+
+/// Directory for files of the instance listening on the given address
+fn instance_dir(addr: SocketAddr) -> PathBuf {
+	env::temp_dir().join(format!("sc2-{}-{}", std::process::id(), addr.port()))
+}
+
+#[cfg(feature = "client")]
+mod pool;
+#[cfg(feature = "client")]
+pub use pool::*;
+
+// todo: can you set both?
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderingLib<'a> {
+	/// Path to `libEGL.so`
+	EGL(&'a Path),
+	/// Path to `libOSMesa.so`
+	OSMesa(&'a Path),
+}
+
+/// How to run the game executable
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Runner<'a> {
+	/// Run the native executable directly
+	#[default]
+	Native,
+	/**
+	Run Windows executable (`SC2_x64.exe`) through Wine or Proton on linux.
+
+	Game directory is looked up in the prefix and paths sent to the game must be
+	[translated](to_windows_path). See `wine_paths` methods of `CreateGame` and `StartReplay`.
+	*/
+	Wine {
+		/// `WINEPREFIX` to use. Defaults to the one from environment or `~/.wine`
+		prefix: Option<&'a Path>,
+		/// Path to `wine` binary. Defaults to `wine` from `PATH`
+		wine_binary: Option<&'a Path>,
+	},
+}
+impl Runner<'_> {
+	pub fn is_wine(&self) -> bool {
+		matches!(self, Self::Wine { .. })
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayMode {
+	Windowed = 0,
+	Borderless = 1,
+	Fullscreen = 2,
+}
+
+const WINDOWS_EXE: &str = "SC2_x64.exe";
+#[cfg(windows)]
+const DEFAULT_EXE: &str = WINDOWS_EXE;
+#[cfg(target_os = "linux")]
+const DEFAULT_EXE: &str = "SC2_x64";
+
+pub fn launcher() -> LauncherBuilder<'static> {
+	Default::default()
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LauncherBuilder<'a> {
+	addr: Addr,
+
+	runner: Runner<'a>,
+	game_dir: PathBuf,
+	version: Option<&'a Path>,
+	select: VersionSelect,
+	executable: Option<&'a Path>,
+	current_dir: Option<&'a Path>,
+
+	data_dir: Option<&'a Path>,
+	temp_dir: Option<&'a Path>,
+	verbose: bool,
+	rendering_lib: Option<RenderingLib<'a>>,
+
+	data_version: Option<&'a OsStr>,
+	display_mode: Option<DisplayMode>,
+	extra: Vec<&'a OsStr>,
+	on_drop: OnDrop,
+	capture: LogCapture,
+
+	auto_temp_dir: bool,
+	process_group: bool,
+	limits: Limits,
+}
+impl LauncherBuilder<'_> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Addres that the SC2 API WebSocket server will listen on.
+	///
+	/// Defaults to `[::1]:5000`
+	pub fn addr(mut self, addr: SocketAddr) -> Self {
+		self.addr = Addr(addr);
+		self
+	}
+	/// Path to the game directory.
+	pub fn game_dir(mut self, path: PathBuf) -> Self {
+		self.game_dir = path;
+		self
+	}
+
+	/// Selects which of the [installed versions](installed_versions) to use.
+	///
+	/// Defaults to `Latest`. Ignored if [`version`](Self::version) folder is set explicitly.
+	pub fn select_version(mut self, select: VersionSelect) -> Self {
+		self.select = select;
+		self
+	}
+	/// Selects `Versions/BaseNNNNN` folder with the given build.
+	///
+	/// Launcher will fail with [`Error::NotInstalled`] if it doesn't exist.
+	pub fn for_base_build(self, base_build: u32) -> Self {
+		self.select_version(VersionSelect::Exact(base_build))
+	}
+
+	/// Enables launched instance to log of all protocol requests/responses to stderr.
+	///
+	/// Use [`capture_logs`](Self::capture_logs) or [`log_to_file`](Self::log_to_file) to keep them.
+	pub fn verbose(mut self, value: bool) -> Self {
+		self.verbose = value;
+		self
+	}
+	/// Keeps the given number of last output lines in [`Instance::logs`].
+	///
+	/// Captured output is parsed into [`LogEvent`]s, so failed launches report how far they got.
+	pub fn capture_logs(mut self, lines: usize) -> Self {
+		self.capture.lines = lines;
+		self
+	}
+	/// Writes whole output of the instance to `sc2.log` in its own temp directory.
+	///
+	/// The file is kept after the instance is closed.
+	pub fn log_to_file(mut self, value: bool) -> Self {
+		self.capture.file = value;
+		self
+	}
+
+	/// Configures SC2 window mode. Can be `Windowed`, `Borderless` or `FullScreen`.
+	///
+	/// If not specified, the game will read the value from `%USERPROFILE%\Documents\StarCraft II\Variables.txt`
+	pub fn display_mode(mut self, display_mode: DisplayMode) -> Self {
+		self.display_mode = Some(display_mode);
+		self
+	}
+
+	/// Creates separate temp directory for each instance and removes it after the instance is closed.
+	///
+	/// Ignored if [`temp_dir`](LauncherBuilder::temp_dir) is set explicitly.
+	pub fn auto_temp_dir(mut self, value: bool) -> Self {
+		self.auto_temp_dir = value;
+		self
+	}
+	/// Launches instance in its own process group, so killing it also kills its children.
+	///
+	/// On windows the whole process tree is killed instead.
+	pub fn process_group(mut self, value: bool) -> Self {
+		self.process_group = value;
+		self
+	}
+	/// Sets resource limits of launched instances
+	pub fn limits(mut self, limits: Limits) -> Self {
+		self.limits = limits;
+		self
+	}
+
+	/// Overrides behaviour after the instance is dropped.
+	///
+	/// Defaults to `Keep`
+	pub fn on_drop(mut self, on_drop: OnDrop) -> Self {
+		self.on_drop = on_drop;
+		self
+	}
+}
+impl<'a> LauncherBuilder<'a> {
+	/// Sets how to run the game. Defaults to `Native`
+	pub fn runner(mut self, runner: Runner<'a>) -> Self {
+		self.runner = runner;
+		self
+	}
+	/// Sets which version to use in `Versions` folder (e.g. `Base75689`)
+	///
+	/// If not set, launcher will pick one of the installed versions according to
+	/// [`select_version`](LauncherBuilder::select_version).
+	pub fn version(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.version = Some(path.as_ref());
+		self
+	}
+	/// Which executable to launch.
+	///
+	/// Defaults to `SC2_x64.exe` on windows or with Wine and `SC2_x64` on linux.
+	pub fn executable(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.executable = Some(path.as_ref());
+		self
+	}
+	/// Sets the working directory for the launched SC2 instance.
+	///
+	/// Defaults to [`game_dir`]`/Support64` on windows or with Wine and [`game_dir`] on linux.
+	///
+	/// [`game_dir`]: Self::game_dir
+	pub fn current_dir(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.current_dir = Some(path.as_ref());
+		self
+	}
+
+	/// Directory with game data passed as `-dataDir`. The game uses its own directory by default
+	pub fn data_dir(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.data_dir = Some(path.as_ref());
+		self
+	}
+	/// Directory for temporary files passed as `-tempDir`
+	pub fn temp_dir(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.temp_dir = Some(path.as_ref());
+		self
+	}
+
+	pub fn egl_path(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.rendering_lib = Some(RenderingLib::EGL(path.as_ref()));
+		self
+	}
+	pub fn osmesa_path(mut self, path: &'a (impl AsRef<Path> + ?Sized)) -> Self {
+		self.rendering_lib = Some(RenderingLib::OSMesa(path.as_ref()));
+		self
+	}
+	/// Sets path to rendering library on linux. It has no effect on windows.
+	pub fn rendering_lib(mut self, lib: RenderingLib<'a>) -> Self {
+		self.rendering_lib = Some(lib);
+		self
+	}
+
+	pub fn data_version(mut self, version_hash: &'a (impl AsRef<OsStr> + ?Sized)) -> Self {
+		self.data_version = Some(version_hash.as_ref());
+		self
+	}
+
+	/// Selects base build and data version of the given release
+	pub fn game_version(mut self, version: &GameVersion) -> Self {
+		self.select = VersionSelect::Exact(version.base_build);
+		self.data_version = Some(OsStr::new(version.data_version));
+		self
+	}
+	/**
+	Selects base build and data version needed to play the replay.
+
+	If replay info doesn't contain data version, it's looked up in [`VERSIONS`] by data build.
+	*/
+	pub fn for_replay(mut self, info: &'a ResponseReplayInfo) -> Self {
+		self.select = VersionSelect::Exact(info.base_build);
+		self.data_version = if info.data_version.is_empty() {
+			GameVersion::by_data_build(info.data_build).map(|v| OsStr::new(v.data_version))
+		} else {
+			Some(OsStr::new(&info.data_version))
+		};
+		self
+	}
+
+	/// Extra launcher arguments.
+	///
+	/// Anything from `%USERPROFILE%\Documents\StarCraft II\Variables.txt` should work.
+	pub fn extra<A: AsRef<OsStr> + ?Sized>(mut self, key: &'a A, value: &'a A) -> Self {
+		self.extra.push(key.as_ref());
+		self.extra.push(value.as_ref());
+		self
+	}
+}
+impl LauncherBuilder<'_> {
+	pub fn build(&self) -> Result<Launcher> {
+		let game_dir = if !self.game_dir.as_os_str().is_empty() {
+			self.game_dir.clone()
+		} else if let Runner::Wine { prefix, .. } = self.runner {
+			find_wine_game_dir(prefix)?
+		} else {
+			find_game_dir()?
+		};
+
+		let default_exe = if self.runner.is_wine() {
+			WINDOWS_EXE
+		} else {
+			DEFAULT_EXE
+		};
+		let executable = self.executable.unwrap_or(Path::new(default_exe));
+		let mut cmd_path = if let Some(version) = self.version {
+			game_dir.join("Versions").join(version)
+		} else {
+			let installed = installed_with(&game_dir, executable)?;
+			self.select.select(&installed)?.path.clone()
+		};
+		cmd_path.push(executable);
+
+		let mut cmd = match self.runner {
+			Runner::Native => Command::new(cmd_path),
+			Runner::Wine {
+				prefix,
+				wine_binary,
+			} => {
+				let mut cmd = Command::new(wine_binary.unwrap_or(Path::new("wine")));
+				cmd.arg(cmd_path);
+				if let Some(prefix) = prefix {
+					cmd.env("WINEPREFIX", prefix);
+				}
+				cmd
+			}
+		};
+		cmd.arg("-listen")
+			.arg(self.addr.0.ip().to_string())
+			.arg("-port")
+			.arg(self.addr.0.port().to_string());
+
+		let path_arg = |path: &Path| -> OsString {
+			if self.runner.is_wine() {
+				to_windows_path(&path.to_string_lossy()).into()
+			} else {
+				path.into()
+			}
+		};
+		let mut sandbox = Sandbox::new(self.process_group, self.limits.wall_time);
+		if let Some(dir) = self.data_dir {
+			cmd.arg("-dataDir").arg(path_arg(dir));
+		}
+		if let Some(dir) = self.temp_dir {
+			cmd.arg("-tempDir").arg(path_arg(dir));
+		} else if self.auto_temp_dir {
+			let dir = sandbox.auto_temp_dir(instance_dir(self.addr.0).join("temp"));
+			cmd.arg("-tempDir").arg(path_arg(dir));
+		}
+
+		if let Some(data_version) = self.data_version {
+			cmd.arg("-dataVersion").arg(data_version);
+		}
+
+		if self.verbose {
+			cmd.arg("-verbose");
+		}
+
+		if let Some(display_mode) = self.display_mode {
+			cmd.arg("-displayMode")
+				.arg((display_mode as u8).to_string());
+		}
+
+		match &self.rendering_lib {
+			None => {}
+			Some(RenderingLib::EGL(path)) => {
+				cmd.arg("-eglpath").arg(path);
+			}
+			Some(RenderingLib::OSMesa(path)) => {
+				cmd.arg("-osmesapath").arg(path);
+			}
+		}
+
+		cmd.args(&self.extra);
+
+		if let Some(current_dir) = self.current_dir {
+			cmd.current_dir(current_dir);
+		} else if self.runner.is_wine() {
+			cmd.current_dir(game_dir.join("Support64"));
+		} else {
+			#[cfg(windows)]
+			let default_work_dir = {
+				let mut dir = game_dir;
+				dir.push("Support64");
+				dir
+			};
+			#[cfg(target_os = "linux")]
+			let default_work_dir = game_dir;
+			cmd.current_dir(default_work_dir);
+		}
+
+		if self.capture.is_enabled() {
+			cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+		}
+
+		#[cfg(target_os = "linux")]
+		{
+			use std::os::unix::process::CommandExt;
+
+			if self.process_group {
+				cmd.process_group(0);
+			}
+			self.limits.apply(&mut cmd);
+		}
+
+		Ok(Launcher {
+			cmd,
+			addr: self.addr.0,
+			on_drop: self.on_drop,
+			capture: self.capture,
+			sandbox,
+		})
+	}
+
+	pub fn spawn(&mut self) -> Result<Instance> {
+		self.build()?.spawn()
+	}
+}
+
+#[derive(Debug)]
+pub struct Launcher {
+	cmd: Command,
+	addr: SocketAddr,
+	on_drop: OnDrop,
+	capture: LogCapture,
+	sandbox: Sandbox,
+}
+impl Launcher {
+	pub fn builder() -> LauncherBuilder<'static> {
+		Default::default()
+	}
+
+	pub fn spawn(&mut self) -> Result<Instance> {
+		let logs = if self.capture.is_enabled() {
+			Some(self.capture.start(self.addr)?)
+		} else {
+			None
+		};
+		let mut sandbox = self.sandbox.prepare()?;
+		let mut child = self.cmd.spawn()?;
+		sandbox.start(Some(child.id()));
+		if let Some(logs) = &logs {
+			if let Some(stdout) = child.stdout.take() {
+				logs.capture(stdout);
+			}
+			if let Some(stderr) = child.stderr.take() {
+				logs.capture(stderr);
+			}
+		}
+		Ok(Instance {
+			sandbox,
+			child,
+			addr: self.addr,
+			on_drop: self.on_drop,
+			logs,
+		})
+	}
+	pub fn output(&mut self) -> Result<Output> {
+		Ok(self.cmd.output()?)
+	}
+	pub fn status(&mut self) -> Result<ExitStatus> {
+		Ok(self.cmd.status()?)
+	}
+
+	pub fn command(&self) -> &Command {
+		&self.cmd
+	}
+	pub fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+	pub fn on_drop(&self) -> OnDrop {
+		self.on_drop
+	}
+	pub fn capture(&self) -> LogCapture {
+		self.capture
+	}
+	pub fn sandbox(&self) -> &Sandbox {
+		&self.sandbox
+	}
+	pub fn into_command(self) -> Command {
+		self.cmd
+	}
+
+	pub fn stdin(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stdin(cfg);
+		self
+	}
+	pub fn stdout(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stdout(cfg);
+		self
+	}
+	pub fn stderr(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+		self.cmd.stderr(cfg);
+		self
+	}
+}
+
+#[derive(Debug)]
+pub struct Instance {
+	pub child: Child,
+	pub addr: SocketAddr,
+	pub on_drop: OnDrop,
+	/// Captured output if enabled with [`LauncherBuilder::capture_logs`] or [`LauncherBuilder::log_to_file`]
+	pub logs: Option<Logs>,
+	pub sandbox: SandboxGuard,
+}
+
+impl Instance {
+	/// Kills the instance together with its process group if it has one
+	pub fn kill(&mut self) -> Result<()> {
+		self.sandbox.kill_group();
+		Ok(self.child.kill()?)
+	}
+
+	/// Checks if the instance process has exited
+	pub fn check_exited(&mut self) -> Result<()> {
+		let Some(status) = self.sandbox.reap(|| self.child.try_wait())? else {
+			return Ok(());
+		};
+		let mut stderr = String::new();
+		if let Some(logs) = &self.logs {
+			logs.wait_closed(LOG_CLOSE_TIMEOUT);
+			stderr = logs.lines().join("\n");
+		} else if let Some(mut pipe) = self.child.stderr.take() {
+			pipe.read_to_string(&mut stderr)?;
+		}
+		Err(Error::Exited {
+			status,
+			stderr,
+			logs: self.logs.clone(),
+		})
+	}
+}
+#[cfg(feature = "client")]
+impl Instance {
+	/**
+	Waits until the instance is ready and connects to it.
+
+	Fails early if the process exits before accepting connection.
+	To get its error output in that case, launch it with piped stderr or captured logs.
+
+	# Examples
+	```no_run
+	use sc2_core::{client::Backoff, launcher::launcher};
+
+	let mut instance = launcher().spawn()?;
+	let mut client = instance.connect(&Backoff::default())?;
+	```
+	*/
+	pub fn connect(&mut self, backoff: &Backoff) -> Result<Client> {
+		let addr = self.addr;
+		Client::connect_retry_with(addr, backoff, || self.check_exited())
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDrop {
+	#[default]
+	Keep,
+	Wait,
+	Kill,
+}
+impl Drop for Instance {
+	fn drop(&mut self) {
+		match self.on_drop {
+			OnDrop::Keep => self.sandbox.keep(),
+			OnDrop::Wait => {
+				let _ = self.child.wait();
+			}
+			OnDrop::Kill => {
+				let _ = self.kill();
+				self.sandbox.stop();
+				let _ = self.child.wait();
+			}
+		}
+	}
+}
+
+/**
+Searches for the game directory in the locations given by [`game_dir_candidates`].
+
+Only installs with native executable are accepted,
+Windows installs in Wine prefixes are found with [`find_wine_game_dir`].
+
+# Errors
+
+Returns [`Error::NoGameDir`] with all rejected candidates if none of them is valid.
+*/
+pub fn find_game_dir() -> Result<PathBuf> {
+	find_game_dir_in(game_dir_candidates(), Path::new(DEFAULT_EXE))
+}
+fn find_game_dir_in(candidates: Vec<PathBuf>, executable: &Path) -> Result<PathBuf> {
+	let mut rejected = vec![];
+	for path in candidates {
+		match check_game_dir(&path, executable) {
+			Ok(()) => return Ok(path),
+			Err(reason) => rejected.push(RejectedDir { path, reason }),
+		}
+	}
+	Err(Error::NoGameDir(rejected))
+}
+
+/// Checks that directory exists and contains at least one `Versions/Base*` folder with the given executable
+pub fn check_game_dir(path: &Path, executable: &Path) -> Result<(), &'static str> {
+	if !path.is_dir() {
+		return Err("directory doesn't exist");
+	}
+	let Ok(versions) = fs::read_dir(path.join("Versions")) else {
+		return Err("no `Versions` directory");
+	};
+	let bases: Vec<_> = versions
+		.filter_map(|e| e.ok())
+		.filter(|e| {
+			e.file_name()
+				.to_str()
+				.is_some_and(|s| s.starts_with("Base"))
+		})
+		.map(|e| e.path())
+		.filter(|p| p.is_dir())
+		.collect();
+	if bases.is_empty() {
+		return Err("no `Versions/Base*` directory");
+	}
+	if !bases.iter().any(|p| p.join(executable).is_file()) {
+		return Err("no `Versions/Base*` directory with the game executable");
+	}
+	Ok(())
+}
+
+/**
+Possible game directory locations in order of priority:
+- `SC2PATH` environment variable
+- Location from `ExecuteInfo.txt` on windows
+- Default install locations: `~/StarCraftII` and `/opt/StarCraftII` on linux
+- Wine prefixes on linux: `$WINEPREFIX`, `~/.wine` and Lutris `~/Games/starcraft-ii` and `~/Games/battlenet`.
+  Location from `ExecuteInfo.txt` in the prefix is checked before the default one
+*/
+pub fn game_dir_candidates() -> Vec<PathBuf> {
+	let mut paths: Vec<PathBuf> = env::var_os("SC2PATH").map(Into::into).into_iter().collect();
+	paths.extend(locate_game_dir());
+
+	#[cfg(windows)]
+	paths.push("C:/Program Files (x86)/StarCraft II".into());
+	#[cfg(target_os = "linux")]
+	{
+		let home = dirs::home_dir();
+		paths.extend(home.as_ref().map(|home| home.join("StarCraftII")));
+		paths.push("/opt/StarCraftII".into());
+
+		let mut prefixes: Vec<PathBuf> = env::var_os("WINEPREFIX")
+			.map(Into::into)
+			.into_iter()
+			.collect();
+		if let Some(home) = &home {
+			prefixes
+				.extend([".wine", "Games/starcraft-ii", "Games/battlenet"].map(|p| home.join(p)));
+		}
+		paths.extend(prefixes.iter().flat_map(|p| wine_game_dir_candidates(p)));
+	}
+	paths
+}
+
+pub fn locate_game_dir() -> Option<PathBuf> {
+	#[cfg(windows)]
+	{
+		let mut path = dirs::document_dir()?;
+		path.extend(["StarCraft II", "ExecuteInfo.txt"]);
+
+		let data = fs::read_to_string(path).ok()?;
+		let mut path: PathBuf = data.get(13..)?.into();
+		for _ in 0..3 {
+			path.pop();
+		}
+
+		path.is_dir().then_some(path)
+	}
+	#[cfg(target_os = "linux")]
+	None
+}
+
+pub fn default_game_dir() -> Option<PathBuf> {
+	#[cfg(windows)]
+	let path = PathBuf::from("C:/Program Files (x86)/StarCraft II");
+	#[cfg(target_os = "linux")]
+	let path = {
+		let mut path = dirs::home_dir()?;
+		path.push("StarCraftII");
+		path
+	};
+
+	path.is_dir().then_some(path)
+}
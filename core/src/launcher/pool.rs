@@ -0,0 +1,251 @@
+use super::*;
+use crate::common::{KindOf, internal::convert_res};
+use sc2_prost::{
+	PortSet, Request, ResponseRestartGame, Status, request::Request as RequestVar,
+	response::Response as ResponseVar,
+};
+use std::{
+	collections::HashSet,
+	io, iter,
+	net::{IpAddr, TcpListener},
+};
+
+/// Attempts to bind a free port before giving up
+const PORT_ATTEMPTS: usize = 16;
+
+/// Ports to use in `JoinGame` requests of multiplayer game
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GamePorts {
+	pub server: PortSet,
+	/// One set for each player except the host
+	pub clients: Vec<PortSet>,
+}
+#[cfg(feature = "request-helpers")]
+impl GamePorts {
+	/// Sets ports of the given request
+	pub fn apply(&self, req: crate::request::JoinGame) -> crate::request::JoinGame {
+		req.server_ports(self.server)
+			.client_ports(self.clients.clone())
+	}
+}
+impl GamePorts {
+	fn ports(&self) -> impl Iterator<Item = u16> + '_ {
+		iter::once(&self.server)
+			.chain(&self.clients)
+			.flat_map(|set| [set.game_port as u16, set.base_port as u16])
+	}
+}
+
+/// Instance handed out by [`InstancePool`] together with connected client
+#[derive(Debug)]
+pub struct Pooled {
+	pub instance: Instance,
+	pub client: Client,
+}
+
+/**
+Pool of SC2 instances listening on automatically allocated ports.
+
+Instances are launched with the given builder on demand and reused between games.
+Consider using [`OnDrop::Kill`] so instances are closed together with the pool.
+
+# Examples
+```no_run
+use sc2_core::launcher::{InstancePool, OnDrop, launcher};
+
+let mut pool = InstancePool::new(launcher().on_drop(OnDrop::Kill));
+let mut instances = pool.acquire_many(2)?;
+let ports = pool.ports(2)?;
+// create and play game
+pool.release_ports(ports);
+for instance in instances {
+	pool.release(instance)?;
+}
+```
+*/
+#[derive(Debug)]
+pub struct InstancePool<'a> {
+	builder: LauncherBuilder<'a>,
+	backoff: Backoff,
+	idle: Vec<Pooled>,
+	used_ports: HashSet<u16>,
+}
+impl<'a> InstancePool<'a> {
+	pub fn new(builder: LauncherBuilder<'a>) -> Self {
+		Self {
+			builder,
+			backoff: Default::default(),
+			idle: vec![],
+			used_ports: Default::default(),
+		}
+	}
+	/// Sets backoff used to connect to freshly launched instances
+	pub fn backoff(mut self, backoff: Backoff) -> Self {
+		self.backoff = backoff;
+		self
+	}
+
+	/// Runner used to launch instances
+	pub fn runner(&self) -> Runner<'a> {
+		self.builder.runner
+	}
+
+	/// Number of idle instances in the pool
+	pub fn idle(&self) -> usize {
+		self.idle.len()
+	}
+
+	fn ip(&self) -> IpAddr {
+		self.builder.addr.0.ip()
+	}
+
+	/// Finds the given number of free ports which aren't in use by the pool
+	fn free_ports(&mut self, count: usize) -> Result<Vec<u16>> {
+		let ip = self.ip();
+		let mut listeners = Vec::with_capacity(count);
+		let mut ports = Vec::with_capacity(count);
+		for _ in 0..count * PORT_ATTEMPTS {
+			if ports.len() == count {
+				break;
+			}
+			let listener = TcpListener::bind((ip, 0))?;
+			let port = listener.local_addr()?.port();
+			if !self.used_ports.contains(&port) && !ports.contains(&port) {
+				ports.push(port);
+			}
+			listeners.push(listener);
+		}
+		if ports.len() < count {
+			return Err(
+				io::Error::new(io::ErrorKind::AddrNotAvailable, "No free ports found").into(),
+			);
+		}
+		self.used_ports.extend(&ports);
+		Ok(ports)
+	}
+
+	/// Launches new instance on a free port and connects to it
+	pub fn spawn(&mut self) -> Result<Pooled> {
+		let port = self.free_ports(1)?[0];
+		let mut instance = self
+			.builder
+			.clone()
+			.addr((self.ip(), port).into())
+			.spawn()?;
+		let client = instance.connect(&self.backoff)?;
+		Ok(Pooled { instance, client })
+	}
+	/// Launches instances in advance until there are at least `count` idle ones
+	pub fn reserve(&mut self, count: usize) -> Result<()> {
+		while self.idle.len() < count {
+			let pooled = self.spawn()?;
+			self.idle.push(pooled);
+		}
+		Ok(())
+	}
+
+	/// Takes idle instance or launches new one
+	pub fn acquire(&mut self) -> Result<Pooled> {
+		match self.idle.pop() {
+			Some(pooled) => Ok(pooled),
+			None => self.spawn(),
+		}
+	}
+	pub fn acquire_many(&mut self, count: usize) -> Result<Vec<Pooled>> {
+		(0..count).map(|_| self.acquire()).collect()
+	}
+
+	/**
+	Allocates ports for the game with the given number of players.
+
+	Ports stay reserved until they are returned with [`release_ports`](Self::release_ports).
+	*/
+	pub fn ports(&mut self, players: usize) -> Result<GamePorts> {
+		let ports = self.free_ports(players.max(1) * 2)?;
+		let mut sets = ports.chunks_exact(2).map(|p| PortSet::from((p[0], p[1])));
+		Ok(GamePorts {
+			server: sets.next().unwrap(),
+			clients: sets.collect(),
+		})
+	}
+	/// Returns ports of the ended game, so they can be handed out again
+	pub fn release_ports(&mut self, ports: GamePorts) {
+		for port in ports.ports() {
+			self.used_ports.remove(&port);
+		}
+	}
+
+	/**
+	Returns instance to the pool.
+
+	Instance leaves current multiplayer game if it's in one,
+	single player games are left as is, since new game can be created over them.
+	If it's in unexpected state or doesn't respond, it's replaced with a new one.
+	*/
+	pub fn release(&mut self, mut pooled: Pooled) -> Result<()> {
+		if !matches!(recycle(&mut pooled.client), Ok(true)) {
+			pooled = self.respawn(pooled)?;
+		}
+		self.idle.push(pooled);
+		Ok(())
+	}
+
+	/**
+	Restarts single player game on the given instance.
+
+	If SC2 reports that it needs hard reset, the instance is replaced with a new one,
+	so the game should be created again.
+	Returns `true` in that case.
+	*/
+	pub fn restart(&mut self, pooled: &mut Pooled) -> Result<bool> {
+		let res = request(
+			&mut pooled.client,
+			RequestVar::RestartGame(Default::default()),
+		)?;
+		let Some(ResponseVar::RestartGame(ResponseRestartGame {
+			need_hard_reset: true,
+			..
+		})) = res.response
+		else {
+			return Ok(false);
+		};
+		let old = std::mem::replace(pooled, self.spawn()?);
+		self.kill(old);
+		Ok(true)
+	}
+
+	fn respawn(&mut self, old: Pooled) -> Result<Pooled> {
+		self.kill(old);
+		self.spawn()
+	}
+	fn kill(&mut self, mut pooled: Pooled) {
+		pooled.instance.on_drop = OnDrop::Kill;
+		self.used_ports.remove(&pooled.instance.addr.port());
+	}
+}
+
+fn request(client: &mut Client, req: RequestVar) -> client::Result<sc2_prost::Response> {
+	let kind = req.kind();
+	let res = client.request(Request {
+		id: 0,
+		request: Some(req),
+	})?;
+	convert_res(res.clone(), kind)?;
+	Ok(res)
+}
+
+/// Brings instance back to `launched` status if possible
+fn recycle(client: &mut Client) -> client::Result<bool> {
+	let res = request(client, RequestVar::Ping(Default::default()))?;
+	match res.status() {
+		Status::Launched => Ok(true),
+		Status::InGame | Status::Ended => {
+			match request(client, RequestVar::LeaveGame(Default::default())) {
+				// single player games can't be left, but can be replaced with `CreateGame`
+				Ok(_) | Err(client::Error::Sc2(_)) => Ok(true),
+				Err(e) => Err(e),
+			}
+		}
+		_ => Ok(false),
+	}
+}
@@ -0,0 +1,321 @@
+use super::*;
+use std::{
+	sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	thread,
+	time::Duration,
+};
+
+/// Resource limits of launched instances. See [`LauncherBuilder::limits`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Limits {
+	/// Maximum size of virtual memory in bytes (`RLIMIT_AS`). Linux only
+	pub address_space: Option<u64>,
+	/// Maximum CPU time in seconds (`RLIMIT_CPU`). Linux only
+	pub cpu_time: Option<u64>,
+	/// Wall-clock time after which the instance is killed by a watchdog thread
+	pub wall_time: Option<Duration>,
+}
+impl Limits {
+	#[cfg(target_os = "linux")]
+	pub(super) fn apply(&self, cmd: &mut Command) {
+		use std::os::unix::process::CommandExt;
+
+		if self.address_space.is_none() && self.cpu_time.is_none() {
+			return;
+		}
+		let limits = *self;
+		// SAFETY: only async-signal-safe `setrlimit` is called between fork and exec
+		unsafe {
+			cmd.pre_exec(move || {
+				if let Some(bytes) = limits.address_space {
+					check(libc::setrlimit(libc::RLIMIT_AS, &rlimit(bytes)))?;
+				}
+				if let Some(secs) = limits.cpu_time {
+					check(libc::setrlimit(libc::RLIMIT_CPU, &rlimit(secs)))?;
+				}
+				Ok(())
+			});
+		}
+	}
+}
+#[cfg(target_os = "linux")]
+fn rlimit(value: u64) -> libc::rlimit {
+	libc::rlimit {
+		rlim_cur: value as libc::rlim_t,
+		rlim_max: value as libc::rlim_t,
+	}
+}
+#[cfg(target_os = "linux")]
+fn check(ret: libc::c_int) -> io::Result<()> {
+	if ret != 0 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(())
+}
+
+/// Temp directory of a single instance
+#[derive(Debug)]
+struct TempDir {
+	path: PathBuf,
+	keep: bool,
+}
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		if self.keep {
+			return;
+		}
+		let _ = fs::remove_dir_all(&self.path);
+		// remove instance dir too unless it still has logs
+		if let Some(parent) = self.path.parent() {
+			let _ = fs::remove_dir(parent);
+		}
+	}
+}
+
+/// Sandboxing configuration of [`Launcher`] shared by the instances it spawns
+#[derive(Debug, Default, Clone)]
+pub struct Sandbox {
+	process_group: bool,
+	wall_time: Option<Duration>,
+	temp_dir: Option<PathBuf>,
+}
+impl Sandbox {
+	pub(super) fn new(process_group: bool, wall_time: Option<Duration>) -> Self {
+		Self {
+			process_group,
+			wall_time,
+			temp_dir: None,
+		}
+	}
+	/// Sets temp directory created for each instance and removed after it's dropped
+	pub(super) fn auto_temp_dir(&mut self, path: PathBuf) -> &Path {
+		self.temp_dir.insert(path)
+	}
+
+	/// Automatically created temp directory
+	pub fn temp_dir(&self) -> Option<&Path> {
+		self.temp_dir.as_deref()
+	}
+
+	/// Prepares sandbox of a new instance before it's spawned, creating its temp directory
+	pub fn prepare(&self) -> io::Result<SandboxGuard> {
+		let temp_dir = match &self.temp_dir {
+			Some(path) => {
+				fs::create_dir_all(path)?;
+				Some(TempDir {
+					path: path.clone(),
+					keep: false,
+				})
+			}
+			None => None,
+		};
+		Ok(SandboxGuard {
+			pid: None,
+			process_group: self.process_group,
+			wall_time: self.wall_time,
+			temp_dir,
+			watchdog: None,
+		})
+	}
+}
+
+/// Stop flag of the watchdog thread
+#[derive(Debug, Default)]
+struct Watchdog {
+	stopped: Mutex<bool>,
+	cond: Condvar,
+}
+impl Watchdog {
+	fn lock(&self) -> MutexGuard<'_, bool> {
+		self.stopped.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	fn stop(&self) {
+		*self.lock() = true;
+		self.cond.notify_all();
+	}
+}
+
+/**
+Sandbox state of a single running instance.
+
+Watchdog is stopped when the guard drops, unless the instance is kept running with [`OnDrop::Keep`]
+(on windows it's stopped either way). Temp directory is removed in the same case.
+*/
+#[derive(Debug)]
+pub struct SandboxGuard {
+	pid: Option<u32>,
+	process_group: bool,
+	wall_time: Option<Duration>,
+	temp_dir: Option<TempDir>,
+	watchdog: Option<Arc<Watchdog>>,
+}
+impl SandboxGuard {
+	/**
+	Attaches the spawned process and starts watchdog for it if wall time is limited.
+
+	`None` means that the process has already exited.
+	*/
+	pub fn start(&mut self, pid: Option<u32>) {
+		self.pid = pid;
+		let (Some(pid), Some(timeout)) = (pid, self.wall_time) else {
+			return;
+		};
+		let watchdog = Arc::new(Watchdog::default());
+		let shared = watchdog.clone();
+		let group = self.process_group;
+		thread::spawn(move || {
+			let stopped = shared.lock();
+			let (stopped, _) = shared
+				.cond
+				.wait_timeout_while(stopped, timeout, |stopped| !*stopped)
+				.unwrap_or_else(PoisonError::into_inner);
+			// lock is held until the kill, so the process can't be reaped with `reap` meanwhile
+			if !*stopped && is_running(pid) {
+				kill_pid(pid, group);
+			}
+		});
+		self.watchdog = Some(watchdog);
+	}
+
+	pub fn temp_dir(&self) -> Option<&Path> {
+		self.temp_dir.as_ref().map(|d| d.path.as_path())
+	}
+	/// Kills the whole process group if the instance was launched in its own one.
+	/// On windows the whole process tree is killed instead.
+	///
+	/// Returns `false` if there's no group.
+	pub fn kill_group(&self) -> bool {
+		self.process_group && self.pid.is_some_and(|pid| kill_pid(pid, true))
+	}
+	/**
+	Leaves temp directory in place and watchdog running for the instance that keeps running.
+
+	On windows the watchdog is stopped instead, since it can't tell
+	if the pid still belongs to the process once its handle is released.
+	*/
+	pub fn keep(&mut self) {
+		if let Some(dir) = &mut self.temp_dir {
+			dir.keep = true;
+		}
+		#[cfg(windows)]
+		self.stop();
+		self.watchdog = None;
+	}
+	/**
+	Runs non blocking check which may reap the process, e.g. [`Child::try_wait`].
+
+	Watchdog can't kill the process during the check,
+	so its pid can't be reused by another process before the kill.
+	*/
+	pub fn reap<T>(&self, check: impl FnOnce() -> T) -> T {
+		let _lock = self.watchdog.as_ref().map(|w| w.lock());
+		check()
+	}
+	/// Stops watchdog. Should be called before waiting for the killed process
+	pub fn stop(&self) {
+		if let Some(watchdog) = &self.watchdog {
+			watchdog.stop();
+		}
+	}
+}
+impl Drop for SandboxGuard {
+	fn drop(&mut self) {
+		self.stop();
+	}
+}
+
+/// Whether the process is still running and wasn't reaped, so its pid belongs to it
+#[cfg(target_os = "linux")]
+fn is_running(pid: u32) -> bool {
+	// SAFETY: `info` is a valid out pointer, `WNOWAIT` leaves the process unreaped
+	unsafe {
+		let mut info: libc::siginfo_t = std::mem::zeroed();
+		let ret = libc::waitid(
+			libc::P_PID,
+			pid as libc::id_t,
+			&mut info,
+			libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+		);
+		// fails if it's not our child anymore, pid is set if it has exited
+		ret == 0 && info.si_pid() == 0
+	}
+}
+#[cfg(windows)]
+fn is_running(_pid: u32) -> bool {
+	// pid can't be reused while the instance holds the process handle,
+	// watchdog is stopped in `keep` before it's released
+	true
+}
+
+#[cfg(target_os = "linux")]
+fn kill_pid(pid: u32, group: bool) -> bool {
+	let pid = pid as libc::pid_t;
+	// SAFETY: sending a signal has no memory safety requirements
+	unsafe {
+		if group {
+			libc::killpg(pid, libc::SIGKILL) == 0
+		} else {
+			libc::kill(pid, libc::SIGKILL) == 0
+		}
+	}
+}
+#[cfg(windows)]
+fn kill_pid(pid: u32, group: bool) -> bool {
+	let mut cmd = Command::new("taskkill");
+	cmd.arg("/F").arg("/PID").arg(pid.to_string());
+	if group {
+		cmd.arg("/T");
+	}
+	cmd.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.status()
+		.is_ok_and(|s| s.success())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+	use super::*;
+	use std::os::unix::process::ExitStatusExt;
+
+	fn sleep(sandbox: &Sandbox) -> (Child, SandboxGuard) {
+		let mut guard = sandbox.prepare().unwrap();
+		let child = Command::new("sleep").arg("10").spawn().unwrap();
+		guard.start(Some(child.id()));
+		(child, guard)
+	}
+
+	#[test]
+	fn watchdog_kills() {
+		let sandbox = Sandbox::new(false, Some(Duration::from_millis(50)));
+		let (mut child, _guard) = sleep(&sandbox);
+		assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+	}
+	#[test]
+	fn watchdog_kept() {
+		let sandbox = Sandbox::new(false, Some(Duration::from_millis(50)));
+		let (mut child, mut guard) = sleep(&sandbox);
+		guard.keep();
+		drop(guard);
+		assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+	}
+	#[test]
+	fn watchdog_stopped() {
+		let sandbox = Sandbox::new(false, Some(Duration::from_millis(50)));
+		let (mut child, guard) = sleep(&sandbox);
+		drop(guard);
+		thread::sleep(Duration::from_millis(100));
+		assert!(child.try_wait().unwrap().is_none());
+		child.kill().unwrap();
+		child.wait().unwrap();
+	}
+	#[test]
+	fn reaped_not_running() {
+		let mut child = Command::new("true").spawn().unwrap();
+		let pid = child.id();
+		while is_running(pid) {
+			thread::sleep(Duration::from_millis(1));
+		}
+		child.wait().unwrap();
+		assert!(!is_running(pid));
+	}
+}
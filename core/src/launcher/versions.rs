@@ -0,0 +1,242 @@
+use super::*;
+
+/// Known SC2 release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GameVersion {
+	/// Game version string (e.g. `4.10.0`)
+	pub label: &'static str,
+	/// Build of the executable in `Versions/BaseNNNNN` folder
+	pub base_build: u32,
+	pub data_build: u32,
+	/// Data version hash passed as `-dataVersion`
+	pub data_version: &'static str,
+}
+impl GameVersion {
+	/// Name of the folder in `Versions` directory containing this version
+	pub fn folder(&self) -> String {
+		format!("Base{}", self.base_build)
+	}
+
+	pub fn by_label(label: &str) -> Option<&'static Self> {
+		VERSIONS.iter().find(|v| v.label == label)
+	}
+	pub fn by_data_build(data_build: u32) -> Option<&'static Self> {
+		VERSIONS.iter().find(|v| v.data_build == data_build)
+	}
+	pub fn by_data_version(data_version: &str) -> Option<&'static Self> {
+		VERSIONS
+			.iter()
+			.find(|v| v.data_version.eq_ignore_ascii_case(data_version))
+	}
+	/// All versions running on the given base build
+	pub fn by_base_build(base_build: u32) -> impl Iterator<Item = &'static Self> {
+		VERSIONS.iter().filter(move |v| v.base_build == base_build)
+	}
+}
+
+/// Version found in `Versions` folder of the game directory
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InstalledVersion {
+	/// Build parsed from `BaseNNNNN` folder name
+	pub base_build: u32,
+	/// Path to the version folder
+	pub path: PathBuf,
+}
+impl InstalledVersion {
+	/// Known releases running on this build
+	pub fn releases(&self) -> impl Iterator<Item = &'static GameVersion> {
+		GameVersion::by_base_build(self.base_build)
+	}
+}
+
+/**
+Lists game versions installed in the given game directory, sorted by build.
+
+Only `BaseNNNNN` folders containing the default executable are included.
+*/
+pub fn installed_versions(game_dir: &Path) -> Result<Vec<InstalledVersion>> {
+	installed_with(game_dir, Path::new(DEFAULT_EXE))
+}
+pub(super) fn installed_with(game_dir: &Path, executable: &Path) -> Result<Vec<InstalledVersion>> {
+	let mut versions = vec![];
+	for entry in fs::read_dir(game_dir.join("Versions"))? {
+		let entry = entry?;
+		let Some(base_build) = entry
+			.file_name()
+			.to_str()
+			.and_then(|s| s.strip_prefix("Base"))
+			.and_then(|s| s.parse().ok())
+		else {
+			continue;
+		};
+		let path = entry.path();
+		if path.join(executable).is_file() {
+			versions.push(InstalledVersion { base_build, path });
+		}
+	}
+	versions.sort_by_key(|v| v.base_build);
+	Ok(versions)
+}
+
+/// Which of the installed versions launcher should use
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VersionSelect {
+	/// Version with the highest build
+	#[default]
+	Latest,
+	/// Version with exactly this build
+	Exact(u32),
+	/// Latest version if its build is not lower than this
+	AtLeast(u32),
+}
+impl VersionSelect {
+	/// Picks version from the list sorted by build
+	pub fn select<'a>(&self, versions: &'a [InstalledVersion]) -> Result<&'a InstalledVersion> {
+		let latest = versions.last().ok_or(Error::NoVersions)?;
+		match *self {
+			Self::Latest => Ok(latest),
+			Self::Exact(build) => versions
+				.iter()
+				.find(|v| v.base_build == build)
+				.ok_or(Error::NotInstalled(build)),
+			Self::AtLeast(build) if latest.base_build >= build => Ok(latest),
+			Self::AtLeast(build) => Err(Error::TooOld {
+				required: build,
+				latest: latest.base_build,
+			}),
+		}
+	}
+}
+
+macro_rules! versions {
+	($($label:literal => $base:literal, $data:literal, $hash:literal;)+) => {
+		/// Registry of known SC2 releases sorted by data build
+		pub const VERSIONS: &[GameVersion] = &[$(GameVersion {
+			label: $label,
+			base_build: $base,
+			data_build: $data,
+			data_version: $hash,
+		}),+];
+	};
+}
+versions! {
+	"4.10.0" => 75689, 75689, "B89B5D6FA7CBF6452E721311BFBC6CB2";
+	"4.10.1" => 75689, 75800, "DDFFF9EC4A171459A4F371C6CC189554";
+	"4.10.2" => 75689, 76052, "D0F1A68AA88BA90369A84CD1439AA1C3";
+	"4.10.3" => 75689, 76114, "CDB276D311F707C29BA664B7754A7293";
+	"4.10.4" => 76811, 76811, "FF9FA4EACEC5F06DEB27BD297D73ED67";
+	"4.11.0" => 77379, 77379, "70E774E722A58287EF37D487605CD384";
+	"4.11.1" => 77379, 77474, "F92D1127A291722120AC816F09B2E583";
+	"4.11.2" => 77535, 77535, "FC43E0897FCC93E4632AC57CBC5A2137";
+	"4.11.3" => 77661, 77661, "A15B8E4247434B020086354F39856C51";
+	"4.11.4" => 78285, 78285, "69493AFAB5C7B45DDB2F3442FD60F0CF";
+	"4.12.0" => 79998, 79998, "B47567DEE5DC23373BFF57194538DFD3";
+	"4.12.1" => 80188, 80188, "44DED5AED024D23177C742FC227C615A";
+	"5.0.0" => 80949, 80949, "9AE39C332883B8BF6AA190286183ED72";
+	"5.0.1" => 81009, 81009, "0D28678BC32E7F67A238F19CD3E0A2CE";
+	"5.0.2" => 81102, 81102, "DC0A1182FB4ABBE8E29E3EC13CF46F68";
+	"5.0.3" => 81433, 81433, "5FD8D4B6B52723B44862DF29F232CF31";
+	"5.0.4" => 82457, 82457, "D2707E265785612D12B381AF6ED9DBF4";
+	"5.0.5" => 82893, 82893, "D795328C01B8A711947CC62AA9750445";
+	"5.0.6" => 83830, 83830, "B4745D6A4F982A3143C183D8ACB6C3E3";
+	"5.0.7" => 84643, 84643, "A389D1F7DF9DD792FBE980533B7119FF";
+	"5.0.8" => 86383, 86383, "22EAC562CD0C6A31FB2C2C21E3AA3680";
+	"5.0.9" => 87702, 87702, "F799E093428D419FD634CCE9B925218C";
+	"5.0.10" => 88500, 88500, "F38043A301B034A78AD13F558257DCF8";
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registry_sorted() {
+		assert!(
+			VERSIONS
+				.windows(2)
+				.all(|v| v[0].data_build < v[1].data_build)
+		);
+		assert!(
+			VERSIONS
+				.windows(2)
+				.all(|v| v[0].base_build <= v[1].base_build)
+		);
+	}
+	#[test]
+	fn lookups() {
+		let v = GameVersion::by_label("4.10.1").unwrap();
+		assert_eq!((v.base_build, v.data_build), (75689, 75800));
+		assert_eq!(v.folder(), "Base75689");
+		assert!(GameVersion::by_label("4.10").is_none());
+
+		assert_eq!(GameVersion::by_data_build(88500).unwrap().label, "5.0.10");
+		assert!(GameVersion::by_data_build(1).is_none());
+
+		let v = GameVersion::by_data_version("b89b5d6fa7cbf6452e721311bfbc6cb2").unwrap();
+		assert_eq!(v.label, "4.10.0");
+
+		let labels: Vec<_> = GameVersion::by_base_build(75689).map(|v| v.label).collect();
+		assert_eq!(labels, ["4.10.0", "4.10.1", "4.10.2", "4.10.3"]);
+		assert_eq!(GameVersion::by_base_build(1).count(), 0);
+	}
+
+	fn installed(builds: &[u32]) -> Vec<InstalledVersion> {
+		builds
+			.iter()
+			.map(|&base_build| InstalledVersion {
+				base_build,
+				path: format!("Base{base_build}").into(),
+			})
+			.collect()
+	}
+	#[test]
+	fn select() {
+		let versions = installed(&[75689, 81009, 88500]);
+		assert_eq!(
+			VersionSelect::Latest.select(&versions).unwrap().base_build,
+			88500
+		);
+		let exact = VersionSelect::Exact(81009).select(&versions).unwrap();
+		assert_eq!(exact.base_build, 81009);
+		assert!(matches!(
+			VersionSelect::Exact(80000).select(&versions),
+			Err(Error::NotInstalled(80000))
+		));
+		let at_least = VersionSelect::AtLeast(80000).select(&versions).unwrap();
+		assert_eq!(at_least.base_build, 88500);
+		assert!(matches!(
+			VersionSelect::AtLeast(90000).select(&versions),
+			Err(Error::TooOld {
+				required: 90000,
+				latest: 88500
+			})
+		));
+		assert!(matches!(
+			VersionSelect::Latest.select(&[]),
+			Err(Error::NoVersions)
+		));
+	}
+	#[test]
+	fn installed_folders() {
+		let dir = env::temp_dir().join(format!("sc2-versions-{}", std::process::id()));
+		let exe = Path::new(DEFAULT_EXE);
+		for (folder, with_exe) in [
+			("Base81009", true),
+			("Base75689", true),
+			("Base88500", false),
+			("BaseX", true),
+			("Other", true),
+		] {
+			let path = dir.join("Versions").join(folder);
+			fs::create_dir_all(&path).unwrap();
+			if with_exe {
+				fs::write(path.join(exe), "").unwrap();
+			}
+		}
+		let versions = installed_with(&dir, exe);
+		fs::remove_dir_all(&dir).unwrap();
+
+		let builds: Vec<_> = versions.unwrap().iter().map(|v| v.base_build).collect();
+		assert_eq!(builds, [75689, 81009]);
+	}
+}
@@ -0,0 +1,116 @@
+use super::*;
+pub use crate::common::to_windows_path;
+
+/// Path of the game inside `drive_c` when it's installed with default settings
+const DEFAULT_INSTALL: &str = "Program Files (x86)/StarCraft II";
+
+/// Resolves prefix to use: the given one, `WINEPREFIX` or `~/.wine`
+pub fn wine_prefix(prefix: Option<&Path>) -> Option<PathBuf> {
+	match prefix {
+		Some(prefix) => Some(prefix.into()),
+		None => env::var_os("WINEPREFIX")
+			.map(Into::into)
+			.or_else(|| Some(dirs::home_dir()?.join(".wine"))),
+	}
+}
+
+/// Converts absolute Windows path to the path in the given prefix
+pub fn from_windows_path(prefix: &Path, path: &str) -> Option<PathBuf> {
+	let (drive, rest) = path.split_once(':')?;
+	let drive = drive.to_ascii_lowercase();
+	let mut out = match drive.as_str() {
+		"c" => prefix.join("drive_c"),
+		"z" => PathBuf::from("/"),
+		_ if drive.len() == 1 => prefix.join("dosdevices").join(format!("{drive}:")),
+		_ => return None,
+	};
+	out.extend(rest.split(['\\', '/']).filter(|s| !s.is_empty()));
+	Some(out)
+}
+
+/// Reads game location from `ExecuteInfo.txt` of any user in the prefix
+pub fn locate_wine_game_dir(prefix: &Path) -> Option<PathBuf> {
+	fs::read_dir(prefix.join("drive_c/users"))
+		.ok()?
+		.filter_map(|e| e.ok())
+		.flat_map(|user| {
+			["Documents", "My Documents"]
+				.map(|docs| user.path().join(docs).join("StarCraft II/ExecuteInfo.txt"))
+		})
+		.find_map(|path| {
+			let data = fs::read_to_string(path).ok()?;
+			let (_, exe) = data.lines().next()?.split_once('=')?;
+			let mut path = from_windows_path(prefix, exe.trim())?;
+			for _ in 0..3 {
+				path.pop();
+			}
+			path.is_dir().then_some(path)
+		})
+}
+
+/// Possible game directory locations inside the prefix
+pub fn wine_game_dir_candidates(prefix: &Path) -> Vec<PathBuf> {
+	let mut paths: Vec<_> = locate_wine_game_dir(prefix).into_iter().collect();
+	paths.push(prefix.join("drive_c").join(DEFAULT_INSTALL));
+	paths
+}
+
+/// Searches for the game directory in the given prefix. See [`wine_prefix`] for defaults
+pub fn find_wine_game_dir(prefix: Option<&Path>) -> Result<PathBuf> {
+	let mut paths: Vec<PathBuf> = env::var_os("SC2PATH").map(Into::into).into_iter().collect();
+	if let Some(prefix) = wine_prefix(prefix) {
+		paths.extend(wine_game_dir_candidates(&prefix));
+	}
+	find_game_dir_in(paths, Path::new(WINDOWS_EXE))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn windows_paths() {
+		assert_eq!(
+			to_windows_path("Maps/Acropolis.SC2Map"),
+			r"Maps\Acropolis.SC2Map"
+		);
+		assert_eq!(
+			to_windows_path("/home/user/a b/x.SC2Replay"),
+			r"Z:\home\user\a b\x.SC2Replay"
+		);
+
+		let prefix = Path::new("/home/user/.wine");
+		assert_eq!(
+			from_windows_path(
+				prefix,
+				r"C:\Program Files (x86)\StarCraft II\Support64\SC2Switcher_x64.exe"
+			),
+			Some(
+				prefix
+					.join("drive_c/Program Files (x86)/StarCraft II/Support64/SC2Switcher_x64.exe")
+			)
+		);
+		assert_eq!(
+			from_windows_path(prefix, "d:/Games"),
+			Some(prefix.join("dosdevices/d:/Games"))
+		);
+		assert_eq!(from_windows_path(prefix, r"Maps\Acropolis.SC2Map"), None);
+		assert_eq!(from_windows_path(prefix, r"\\server:share\x"), None);
+	}
+	#[cfg(unix)]
+	#[test]
+	fn round_trip() {
+		let prefix = Path::new("/home/user/.wine");
+		for path in [
+			"/home/user/replays/x.SC2Replay",
+			"/opt/StarCraftII/Maps/a b.SC2Map",
+			"/",
+		] {
+			let windows = to_windows_path(path);
+			assert_eq!(
+				from_windows_path(prefix, &windows),
+				Some(PathBuf::from(path))
+			);
+		}
+	}
+}
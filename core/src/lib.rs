@@ -11,7 +11,7 @@ Start by looking into [`Client`] documentation.
 - [x] Check response kind/status/errors
 - [x] Optional methods for easier request making
 - [x] SC2 Instance launching
-- [ ] Proxy between client and sc2
+- [x] Proxy between client and sc2
 - [x] Split send and recieve
 - [ ] Update async
 - [x] Auto locate game dir
@@ -22,14 +22,23 @@ use thiserror::Error;
 #[cfg(feature = "client")]
 pub mod client;
 #[cfg(feature = "client")]
-pub use client::{Client, Error, Result};
+pub use client::{Client, Error, Result, Transport};
 
 #[cfg(feature = "server")]
 pub mod server;
 
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[cfg(feature = "proxy")]
+pub mod proxy;
+
 #[cfg(feature = "launcher")]
 pub mod launcher;
 
+#[cfg(feature = "record")]
+pub mod record;
+
 #[cfg(feature = "request-helpers")]
 pub mod request;
 
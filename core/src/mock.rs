@@ -0,0 +1,337 @@
+/*!
+Scriptable stand-in for SC2, useful to test bots without the game.
+
+[`MockSc2`] follows SC2 status transitions
+(`launched` → `init_game` → `in_game` → `ended`),
+rejects requests which are invalid for the current status
+and answers the rest with user-supplied fixtures, closures or defaults.
+
+# Examples
+```no_run
+use sc2_core::{Client, mock::MockSc2, request::Ping};
+
+let (addr, handle) = MockSc2::new().game_length(100).spawn("127.0.0.1:0")?;
+
+let mut client = Client::connect(addr)?;
+let res = client.request(Ping)?;
+println!("{res:?}");
+drop(client);
+
+let mock = handle.join().unwrap()?;
+```
+*/
+
+use super::*;
+use crate::server::{Result, Server, ServerClient};
+use sc2_prost::{
+	ActionResult, Observation, ResponseAction, ResponseJoinGame, ResponseObservation, ResponsePing,
+	ResponseStep, Status,
+};
+use std::{
+	collections::HashMap,
+	fmt,
+	net::{SocketAddr, ToSocketAddrs},
+	thread,
+};
+
+/// Game state tracked by [`MockSc2`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MockState {
+	pub status: Status,
+	pub game_loop: u32,
+}
+
+/**
+Closure answering requests of some [`Kind`].
+
+It's called with the state already transitioned according to the request,
+so it can be changed to simulate something else (e.g. failed join or game end).
+*/
+pub type Handler = Box<dyn FnMut(RequestVar, &mut MockState) -> ResponseVar + Send>;
+
+/// Mock SC2 instance. See [module docs](self) for details.
+pub struct MockSc2 {
+	state: MockState,
+	game_length: Option<u32>,
+	handlers: HashMap<Kind, Handler>,
+}
+impl fmt::Debug for MockSc2 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("MockSc2")
+			.field("state", &self.state)
+			.field("game_length", &self.game_length)
+			.field("handlers", &self.handlers.keys())
+			.finish()
+	}
+}
+impl Default for MockSc2 {
+	fn default() -> Self {
+		Self {
+			state: MockState {
+				status: Status::Launched,
+				game_loop: 0,
+			},
+			game_length: None,
+			handlers: Default::default(),
+		}
+	}
+}
+impl MockSc2 {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Ends the game when game loop reaches the given value
+	pub fn game_length(mut self, game_loops: u32) -> Self {
+		self.game_length = Some(game_loops);
+		self
+	}
+	/// Sets handler for requests of the given kind
+	pub fn on(
+		mut self,
+		kind: Kind,
+		handler: impl FnMut(RequestVar, &mut MockState) -> ResponseVar + Send + 'static,
+	) -> Self {
+		self.handlers.insert(kind, Box::new(handler));
+		self
+	}
+	/// Always answers requests of the given kind with the same response
+	pub fn respond(self, response: ResponseVar) -> Self {
+		self.on(response.kind(), move |_, _| response.clone())
+	}
+
+	pub fn state(&self) -> MockState {
+		self.state
+	}
+	pub fn state_mut(&mut self) -> &mut MockState {
+		&mut self.state
+	}
+
+	/// Returns response to the given request and updates state
+	pub fn handle(&mut self, req: Request) -> Response {
+		let id = req.id;
+		let Some(req) = req.request else {
+			return self.error(id, "Empty request");
+		};
+		let kind = req.kind();
+		if !kind.is_allowed_in(self.state.status) {
+			let status = self.state.status;
+			return self.error(
+				id,
+				format!("{kind:?} request is invalid in {status:?} status"),
+			);
+		}
+
+		self.transition(&req);
+		let res = match self.handlers.get_mut(&kind) {
+			Some(handler) => handler(req, &mut self.state),
+			None => default_response(req, &self.state),
+		};
+		Response {
+			id,
+			response: Some(res),
+			error: vec![],
+			status: self.state.status as i32,
+		}
+	}
+
+	fn error(&self, id: u32, err: impl Into<String>) -> Response {
+		Response {
+			id,
+			response: None,
+			error: vec![err.into()],
+			status: self.state.status as i32,
+		}
+	}
+
+	fn transition(&mut self, req: &RequestVar) {
+		let state = &mut self.state;
+		match req {
+			RequestVar::CreateGame(_) => state.status = Status::InitGame,
+			RequestVar::JoinGame(_) | RequestVar::RestartGame(_) => {
+				state.status = Status::InGame;
+				state.game_loop = 0;
+			}
+			RequestVar::StartReplay(_) => {
+				state.status = Status::InReplay;
+				state.game_loop = 0;
+			}
+			RequestVar::LeaveGame(_) => {
+				state.status = Status::Launched;
+				state.game_loop = 0;
+			}
+			RequestVar::Quit(_) => state.status = Status::Quit,
+			RequestVar::Step(step) => {
+				state.game_loop = state.game_loop.saturating_add(step.count.max(1));
+				if let Some(length) = self.game_length
+					&& state.game_loop >= length
+				{
+					state.game_loop = length;
+					state.status = Status::Ended;
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Answers requests of the connected client until it quits or disconnects
+	pub fn serve(&mut self, client: &mut ServerClient) -> Result {
+		while self.state.status != Status::Quit {
+			let req = match client.read() {
+				Ok(req) => req,
+				Err(e) if e.is_disconnect() => break,
+				Err(e) => return Err(e),
+			};
+			client.send(self.handle(req))?;
+		}
+		Ok(())
+	}
+
+	/**
+	Binds to the given address and serves a single client in the background thread.
+
+	Returns the address to connect to and handle to get the mock back when client is done.
+	*/
+	pub fn spawn(
+		mut self,
+		addr: impl ToSocketAddrs,
+	) -> Result<(SocketAddr, thread::JoinHandle<Result<Self>>)> {
+		let server = Server::bind(addr)?;
+		let addr = server.local_addr()?;
+		let handle = thread::spawn(move || {
+			let (mut client, _) = server.accept()?;
+			self.serve(&mut client)?;
+			Ok(self)
+		});
+		Ok((addr, handle))
+	}
+}
+
+fn default_response(req: RequestVar, state: &MockState) -> ResponseVar {
+	use RequestVar as Req;
+	use ResponseVar as Res;
+	match req {
+		Req::CreateGame(_) => Res::CreateGame(Default::default()),
+		Req::JoinGame(_) => Res::JoinGame(ResponseJoinGame {
+			player_id: 1,
+			..Default::default()
+		}),
+		Req::RestartGame(_) => Res::RestartGame(Default::default()),
+		Req::StartReplay(_) => Res::StartReplay(Default::default()),
+		Req::LeaveGame(_) => Res::LeaveGame(Default::default()),
+		Req::QuickSave(_) => Res::QuickSave(Default::default()),
+		Req::QuickLoad(_) => Res::QuickLoad(Default::default()),
+		Req::Quit(_) => Res::Quit(Default::default()),
+		Req::GameInfo(_) => Res::GameInfo(Default::default()),
+		Req::Observation(_) => Res::Observation(ResponseObservation {
+			observation: Some(Observation {
+				game_loop: state.game_loop,
+				..Default::default()
+			}),
+			..Default::default()
+		}),
+		Req::Action(action) => Res::Action(ResponseAction {
+			result: vec![ActionResult::Success as i32; action.actions.len()],
+		}),
+		Req::ObsAction(_) => Res::ObsAction(Default::default()),
+		Req::Step(_) => Res::Step(ResponseStep {
+			simulation_loop: state.game_loop,
+		}),
+		Req::Data(_) => Res::Data(Default::default()),
+		Req::Query(_) => Res::Query(Default::default()),
+		Req::SaveReplay(_) => Res::SaveReplay(Default::default()),
+		Req::ReplayInfo(_) => Res::ReplayInfo(Default::default()),
+		Req::AvailableMaps(_) => Res::AvailableMaps(Default::default()),
+		Req::SaveMap(_) => Res::SaveMap(Default::default()),
+		Req::MapCommand(_) => Res::MapCommand(Default::default()),
+		Req::Ping(_) => Res::Ping(ResponsePing {
+			game_version: "mock".into(),
+			..Default::default()
+		}),
+		Req::Debug(_) => Res::Debug(Default::default()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc2_prost::RequestStep;
+
+	fn req(request: RequestVar) -> Request {
+		Request {
+			id: 0,
+			request: Some(request),
+		}
+	}
+
+	#[test]
+	fn transitions() {
+		let (addr, handle) = MockSc2::new().game_length(10).spawn("127.0.0.1:0").unwrap();
+		let mut client = Client::connect(addr).unwrap();
+		let mut request = |r| client.request(req(r)).unwrap();
+
+		let res = request(RequestVar::Ping(Default::default()));
+		assert_eq!(res.status(), Status::Launched);
+		let res = request(RequestVar::CreateGame(Default::default()));
+		assert_eq!(res.status(), Status::InitGame);
+		let res = request(RequestVar::JoinGame(Default::default()));
+		assert_eq!(res.status(), Status::InGame);
+
+		let res = request(RequestVar::Step(RequestStep { count: 4 }));
+		assert_eq!(res.status(), Status::InGame);
+		assert!(matches!(
+			res.response,
+			Some(ResponseVar::Step(ResponseStep { simulation_loop: 4 }))
+		));
+		let res = request(RequestVar::Step(RequestStep { count: 8 }));
+		assert_eq!(res.status(), Status::Ended);
+		assert!(matches!(
+			res.response,
+			Some(ResponseVar::Step(ResponseStep {
+				simulation_loop: 10
+			}))
+		));
+
+		let res = request(RequestVar::LeaveGame(Default::default()));
+		assert_eq!(res.status(), Status::Launched);
+		let res = request(RequestVar::Quit(Default::default()));
+		assert_eq!(res.status(), Status::Quit);
+
+		let mock = handle.join().unwrap().unwrap();
+		assert_eq!(
+			mock.state(),
+			MockState {
+				status: Status::Quit,
+				game_loop: 0,
+			}
+		);
+	}
+	#[test]
+	fn invalid_requests() {
+		let (addr, handle) = MockSc2::new().spawn("127.0.0.1:0").unwrap();
+		let mut client = Client::connect(addr).unwrap();
+		// let the mock reject them instead of the client
+		client.set_check_status(false);
+		let mut request = |r| client.request(req(r)).unwrap();
+
+		let res = request(RequestVar::Step(Default::default()));
+		assert!(res.response.is_none());
+		assert_eq!(res.error.len(), 1);
+		assert_eq!(res.status(), Status::Launched);
+
+		request(RequestVar::CreateGame(Default::default()));
+		let res = request(RequestVar::CreateGame(Default::default()));
+		assert!(res.response.is_none());
+		assert_eq!(res.status(), Status::InitGame);
+
+		let res = request(RequestVar::Observation(Default::default()));
+		assert!(res.response.is_none());
+		assert_eq!(res.status(), Status::InitGame);
+
+		drop(client);
+		assert_eq!(
+			handle.join().unwrap().unwrap().state().status,
+			Status::InitGame
+		);
+	}
+}
@@ -0,0 +1,163 @@
+/*!
+Transparent proxy between bot and SC2 with hooks to inspect traffic.
+
+[`Proxy`] reads requests of the bot connected to [`Server`],
+passes them through [`ProxyHooks`] and forwards to SC2 via [`Client`].
+Responses go the same way back.
+Hooks can observe, modify, delay, drop or answer messages on their own,
+which is enough to log traffic or enforce time limits.
+
+# Examples
+```no_run
+use sc2_core::{
+	Client,
+	common::{Kind, KindOf},
+	proxy::{Proxy, ProxyHooks, Verdict},
+	server::Server,
+	sc2_prost::{Request, Response},
+};
+
+struct Logger;
+impl ProxyHooks for Logger {
+	fn on_request(&mut self, req: Request) -> Verdict<Request> {
+		println!("-> {:?}", req.kind());
+		Verdict::Forward(req)
+	}
+	fn on_response(&mut self, kind: Kind, res: Response) -> Verdict<Response> {
+		println!("<- {kind:?}");
+		Verdict::Forward(res)
+	}
+}
+
+let server = Server::bind("127.0.0.1:5000")?;
+let sc2 = Client::connect("127.0.0.1:5001")?;
+let mut proxy = Proxy::accept(&server, sc2, Logger)?;
+proxy.run()?;
+```
+*/
+
+use super::*;
+use crate::server::{self, Server, ServerClient};
+use std::{thread, time::Duration};
+
+/// Possible [`Proxy`] errors
+#[derive(Debug, Error)]
+pub enum Error {
+	/// Error communicating with bot
+	#[error("Bot error: {0}")]
+	Bot(#[from] server::Error),
+	/// Error communicating with SC2
+	#[error("SC2 error: {0}")]
+	Sc2(#[from] client::Error),
+}
+
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+/// What to do with the intercepted message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict<T> {
+	/// Pass the message further
+	Forward(T),
+	/// Pass the message further after the given delay
+	Delay(T, Duration),
+	/// Answer the bot with the given response instead.
+	/// For requests this means SC2 won't receive it.
+	Reply(Box<Response>),
+	/// Don't pass the message
+	Drop,
+}
+
+/**
+Hooks called by [`Proxy`] for every message.
+
+All methods forward messages unchanged by default,
+so `()` can be used for a transparent proxy.
+*/
+pub trait ProxyHooks {
+	/// Called for every request received from the bot
+	fn on_request(&mut self, req: Request) -> Verdict<Request> {
+		Verdict::Forward(req)
+	}
+	/// Called for every response received from SC2 to the request of the given kind
+	fn on_response(&mut self, kind: Kind, res: Response) -> Verdict<Response> {
+		let _ = kind;
+		Verdict::Forward(res)
+	}
+}
+impl ProxyHooks for () {}
+
+/// Proxy between single bot and SC2. See [module docs](self) for details.
+#[derive(Debug)]
+pub struct Proxy<H> {
+	bot: ServerClient,
+	sc2: Client,
+	hooks: H,
+}
+impl<H: ProxyHooks> Proxy<H> {
+	/// Status checks of the client are disabled, so SC2 itself responds to invalid requests
+	pub fn new(bot: ServerClient, mut sc2: Client, hooks: H) -> Self {
+		sc2.set_check_status(false);
+		Self { bot, sc2, hooks }
+	}
+	/// Waits for the bot to connect to the given server
+	pub fn accept(server: &Server, sc2: Client, hooks: H) -> Result<Self> {
+		let (bot, _) = server.accept()?;
+		Ok(Self::new(bot, sc2, hooks))
+	}
+
+	/// Proxies messages until the bot disconnects or SC2 quits
+	pub fn run(&mut self) -> Result {
+		loop {
+			let req = match self.bot.read() {
+				Ok(req) => req,
+				Err(e) if e.is_disconnect() => break Ok(()),
+				Err(e) => break Err(e.into()),
+			};
+			let kind = req.kind();
+			if let Some(res) = self.forward(req)? {
+				self.reply(kind, res)?;
+			}
+			if kind == Kind::Quit {
+				break Ok(());
+			}
+		}
+	}
+
+	/// Passes the request to SC2 and returns its response if there is one
+	fn forward(&mut self, req: Request) -> Result<Option<Response>> {
+		let req = match self.hooks.on_request(req) {
+			Verdict::Forward(req) => req,
+			Verdict::Delay(req, delay) => {
+				thread::sleep(delay);
+				req
+			}
+			Verdict::Reply(res) => return Ok(Some(*res)),
+			Verdict::Drop => return Ok(None),
+		};
+		self.sc2.send(req)?;
+		Ok(Some(self.sc2.read::<Request>()?))
+	}
+
+	fn reply(&mut self, kind: Kind, res: Response) -> Result {
+		let res = match self.hooks.on_response(kind, res) {
+			Verdict::Forward(res) => res,
+			Verdict::Reply(res) => *res,
+			Verdict::Delay(res, delay) => {
+				thread::sleep(delay);
+				res
+			}
+			Verdict::Drop => return Ok(()),
+		};
+		Ok(self.bot.send(res)?)
+	}
+
+	pub fn hooks(&self) -> &H {
+		&self.hooks
+	}
+	pub fn hooks_mut(&mut self) -> &mut H {
+		&mut self.hooks
+	}
+	pub fn into_parts(self) -> (ServerClient, Client, H) {
+		(self.bot, self.sc2, self.hooks)
+	}
+}
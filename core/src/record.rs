@@ -0,0 +1,467 @@
+/*!
+Recording of protocol traffic and its deterministic playback.
+
+Traffic of [`Client`] is recorded with [`Client::record`]
+into a sequence of length-delimited protobuf [`Entry`]s.
+The record can then be played back with [`RecordedClient`],
+which implements [`Transport`] like [`Client`] does without any SC2 process,
+so the same bot code can be run against recorded observations.
+
+# Examples
+Recording:
+```no_run
+use sc2_core::record::Recorder;
+
+client.record(Recorder::create("game.sc2rec")?);
+// play the game as usual
+let recorder = client.stop_recording();
+```
+Playback:
+```no_run
+use sc2_core::record::RecordedClient;
+
+let mut client = RecordedClient::open("game.sc2rec")?;
+// run the same bot code on this client
+for m in client.mismatches() {
+	println!("Request #{} differs: {:?} != {:?}", m.index, m.actual, m.expected);
+}
+```
+*/
+
+use super::*;
+use client::Transport;
+use prost::Message as _;
+use sc2_prost::Status;
+use std::{
+	collections::VecDeque,
+	fmt,
+	fs::{self, File},
+	io::{self, BufWriter, Write},
+	path::Path,
+	time::Instant,
+};
+use tungstenite::Error as WsError;
+
+/// Single recorded request/response pair
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Entry {
+	/// Microseconds since the recording start when request was sent
+	#[prost(uint64, tag = "1")]
+	pub request_time_us: u64,
+	/// Microseconds since the recording start when response was received
+	#[prost(uint64, tag = "2")]
+	pub response_time_us: u64,
+	#[prost(message, optional, tag = "3")]
+	pub request: Option<Request>,
+	#[prost(message, optional, tag = "4")]
+	pub response: Option<Response>,
+}
+
+/**
+Writes request/response pairs into the given output.
+
+Responses are matched to requests by their ids,
+requests without id are matched in the order they were sent.
+Responses to unknown requests are recorded without request,
+requests left without response are written when the recorder is dropped.
+
+Write errors don't fail the client, the first one is kept in [`error`](Self::error)
+and nothing is written after it.
+*/
+pub struct Recorder {
+	out: Box<dyn Write + Send>,
+	start: Instant,
+	sent: VecDeque<(u64, Request)>,
+	error: Option<io::Error>,
+}
+impl fmt::Debug for Recorder {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Recorder")
+			.field("start", &self.start)
+			.field("sent", &self.sent)
+			.field("error", &self.error)
+			.finish_non_exhaustive()
+	}
+}
+impl Recorder {
+	pub fn new(out: impl Write + Send + 'static) -> Self {
+		Self {
+			out: Box::new(out),
+			start: Instant::now(),
+			sent: Default::default(),
+			error: None,
+		}
+	}
+	/// Creates recorder writing into the file at the given path
+	pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+		Ok(Self::new(BufWriter::new(File::create(path)?)))
+	}
+
+	fn now(&self) -> u64 {
+		self.start.elapsed().as_micros() as u64
+	}
+
+	pub fn request(&mut self, req: &Request) {
+		self.sent.push_back((self.now(), req.clone()));
+	}
+	pub fn response(&mut self, res: &Response) {
+		// responses without id belong to the oldest request without id
+		let pos = self.sent.iter().position(|(_, req)| req.id == res.id);
+		let (request_time_us, request) = pos.and_then(|i| self.sent.remove(i)).unzip();
+		self.write_entry(Entry {
+			request_time_us: request_time_us.unwrap_or_default(),
+			response_time_us: self.now(),
+			request,
+			response: Some(res.clone()),
+		})
+	}
+	fn write_entry(&mut self, entry: Entry) {
+		if self.error.is_none() {
+			// a partially written entry breaks the rest of the record
+			if let Err(e) = self.out.write_all(&entry.encode_length_delimited_to_vec()) {
+				self.error = Some(e);
+			}
+		}
+	}
+
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.out.flush()
+	}
+	/// First error occurred while writing the record
+	pub fn error(&self) -> Option<&io::Error> {
+		self.error.as_ref()
+	}
+}
+impl Drop for Recorder {
+	fn drop(&mut self) {
+		for (request_time_us, req) in std::mem::take(&mut self.sent) {
+			self.write_entry(Entry {
+				request_time_us,
+				response_time_us: 0,
+				request: Some(req),
+				response: None,
+			});
+		}
+		let _ = self.out.flush();
+	}
+}
+
+/// Reads all entries from the record
+pub fn read_entries(mut buf: &[u8]) -> Result<Vec<Entry>> {
+	let mut entries = vec![];
+	while !buf.is_empty() {
+		entries.push(Entry::decode_length_delimited(&mut buf)?);
+	}
+	Ok(entries)
+}
+
+/// Request sent during playback which differs from the recorded one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+	/// Index of the entry in the record
+	pub index: usize,
+	/// Recorded request
+	pub expected: Option<Request>,
+	/// Request sent during playback
+	pub actual: Request,
+}
+
+/**
+Plays back recorded traffic with the same API as [`Client`], also through [`Transport`].
+
+Each sent request is answered with the next recorded response,
+regardless of what was actually sent.
+Requests which differ from the recorded ones are collected as [`Mismatch`]es.
+When the record is exhausted, requests fail as if the connection was closed.
+*/
+#[derive(Debug, Default)]
+pub struct RecordedClient {
+	entries: Vec<Entry>,
+	next: usize,
+	responses: VecDeque<Response>,
+	mismatches: Vec<Mismatch>,
+	pipeline: Pipeline,
+	status: Option<Status>,
+}
+impl RecordedClient {
+	pub fn new(entries: Vec<Entry>) -> Self {
+		Self {
+			entries,
+			..Default::default()
+		}
+	}
+	/// Loads record from the file at the given path
+	pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+		Ok(Self::new(read_entries(&fs::read(path)?)?))
+	}
+
+	pub fn entries(&self) -> &[Entry] {
+		&self.entries
+	}
+	/// Number of entries played back so far
+	pub fn position(&self) -> usize {
+		self.next
+	}
+	pub fn is_finished(&self) -> bool {
+		self.next >= self.entries.len() && self.responses.is_empty()
+	}
+	pub fn mismatches(&self) -> &[Mismatch] {
+		&self.mismatches
+	}
+	pub fn take_mismatches(&mut self) -> Vec<Mismatch> {
+		std::mem::take(&mut self.mismatches)
+	}
+	/// Status from the last played back response
+	pub fn status(&self) -> Option<Status> {
+		self.status
+	}
+
+	pub fn request<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<R::Output> {
+		if self.pipeline.check_idle().is_err() {
+			let pending = self.submit(request)?;
+			return self.resolve(pending);
+		}
+		self.send(request)?;
+		self.read::<R>()
+	}
+
+	fn _read(&mut self) -> Result<Response> {
+		let res = self
+			.responses
+			.pop_front()
+			.ok_or(Error::WebSocket(WsError::ConnectionClosed))?;
+		self.status = Some(res.status());
+		Ok(res)
+	}
+	pub fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		while self.pipeline.only_cancelled() {
+			let res = self._read()?;
+			self.pipeline.accept(res, 0)?;
+		}
+		self.pipeline.check_idle()?;
+		R::parse(self._read()?)
+	}
+
+	pub fn send(&mut self, request: impl Into<Request>) -> Result {
+		self.write(request)
+	}
+
+	fn _write(&mut self, req: Request) -> Result {
+		let index = self.next;
+		let Some(entry) = self.entries.get(index) else {
+			return Err(WsError::ConnectionClosed.into());
+		};
+		self.next += 1;
+		if entry.request.as_ref() != Some(&req) {
+			self.mismatches.push(Mismatch {
+				index,
+				expected: entry.request.clone(),
+				actual: req.clone(),
+			});
+		}
+		if let Some(mut res) = entry.response.clone() {
+			res.id = req.id;
+			self.responses.push_back(res);
+		}
+		Ok(())
+	}
+	pub fn write(&mut self, request: impl Into<Request>) -> Result {
+		self._write(request.into())
+	}
+
+	pub fn flush(&mut self) -> Result {
+		Ok(())
+	}
+
+	/// Same as [`Client::submit`]
+	pub fn submit<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<Pending<R>> {
+		let mut req = request.into();
+		req.id = self.pipeline.next_id();
+		let id = req.id;
+		self._write(req)?;
+		Ok(self.pipeline.push(id))
+	}
+	/// Same as [`Client::resolve`]
+	pub fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		let id = pending.id;
+		if let Some(res) = self.pipeline.take(id)? {
+			return R::parse(res);
+		}
+		loop {
+			let res = self._read()?;
+			if let Some(res) = self.pipeline.accept(res, id)? {
+				break R::parse(res);
+			}
+		}
+	}
+}
+impl Transport for RecordedClient {
+	fn request<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<R::Output> {
+		RecordedClient::request(self, request)
+	}
+	fn read<R: ParseResponse>(&mut self) -> Result<R::Output> {
+		RecordedClient::read::<R>(self)
+	}
+	fn send(&mut self, request: impl Into<Request>) -> Result {
+		RecordedClient::send(self, request)
+	}
+	fn write(&mut self, request: impl Into<Request>) -> Result {
+		RecordedClient::write(self, request)
+	}
+	fn flush(&mut self) -> Result {
+		RecordedClient::flush(self)
+	}
+	fn submit<R: Into<Request> + ParseResponse>(&mut self, request: R) -> Result<Pending<R>> {
+		RecordedClient::submit(self, request)
+	}
+	fn resolve<R: ParseResponse>(&mut self, pending: Pending<R>) -> Result<R::Output> {
+		RecordedClient::resolve(self, pending)
+	}
+	fn status(&self) -> Option<Status> {
+		RecordedClient::status(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc2_prost::{RequestPing, RequestQuit, ResponsePing};
+	use std::sync::{Arc, Mutex};
+
+	#[derive(Clone, Default)]
+	struct Buf(Arc<Mutex<Vec<u8>>>);
+	impl Write for Buf {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().unwrap().write(buf)
+		}
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	fn ping(id: u32) -> Request {
+		Request {
+			id,
+			request: Some(RequestVar::Ping(RequestPing {})),
+		}
+	}
+	fn pong(id: u32, game_version: &str) -> Response {
+		Response {
+			id,
+			response: Some(ResponseVar::Ping(ResponsePing {
+				game_version: game_version.into(),
+				..Default::default()
+			})),
+			..Default::default()
+		}
+	}
+	fn quit() -> Request {
+		Request {
+			id: 0,
+			request: Some(RequestVar::Quit(RequestQuit {})),
+		}
+	}
+	fn version(res: &Response) -> &str {
+		match &res.response {
+			Some(ResponseVar::Ping(res)) => &res.game_version,
+			_ => panic!("not a ping response: {res:?}"),
+		}
+	}
+
+	fn record(f: impl FnOnce(&mut Recorder)) -> Vec<Entry> {
+		let buf = Buf::default();
+		let mut recorder = Recorder::new(buf.clone());
+		f(&mut recorder);
+		drop(recorder);
+		read_entries(&buf.0.lock().unwrap()).unwrap()
+	}
+	// the same traffic as the client in `playback` makes
+	fn game(recorder: &mut Recorder) {
+		recorder.request(&ping(0));
+		recorder.response(&pong(0, "a"));
+		recorder.request(&ping(1));
+		recorder.request(&ping(2));
+		recorder.response(&pong(1, "b"));
+		recorder.response(&pong(2, "c"));
+		recorder.request(&quit());
+	}
+
+	#[test]
+	fn pairing() {
+		let entries = record(|recorder| {
+			recorder.request(&ping(1));
+			recorder.request(&ping(2));
+			recorder.request(&ping(0));
+			recorder.request(&ping(0));
+			recorder.response(&pong(2, "b"));
+			recorder.response(&pong(0, "c"));
+			recorder.response(&pong(1, "a"));
+			recorder.response(&pong(3, "x"));
+		});
+		let pairs: Vec<_> = entries
+			.iter()
+			.map(|e| (e.request.clone(), e.response.as_ref().map(version)))
+			.collect();
+		assert_eq!(
+			pairs,
+			[
+				(Some(ping(2)), Some("b")),
+				(Some(ping(0)), Some("c")),
+				(Some(ping(1)), Some("a")),
+				(None, Some("x")),
+				(Some(ping(0)), None),
+			]
+		);
+	}
+	#[test]
+	fn playback() {
+		let mut client = RecordedClient::new(record(game));
+		assert_eq!(version(&client.request(ping(0)).unwrap()), "a");
+		let b = client.submit(ping(0)).unwrap();
+		let c = client.submit(ping(0)).unwrap();
+		assert_eq!(version(&client.resolve(c).unwrap()), "c");
+		assert_eq!(version(&client.resolve(b).unwrap()), "b");
+		assert!(client.request(quit()).is_err());
+		assert!(client.is_finished());
+		assert!(client.mismatches().is_empty());
+		assert!(client.request(ping(0)).is_err());
+	}
+	#[test]
+	fn mismatches() {
+		let mut client = RecordedClient::new(record(game));
+		assert_eq!(version(&client.request(quit()).unwrap()), "a");
+		client.request(ping(0)).unwrap();
+		assert_eq!(
+			client.take_mismatches(),
+			[
+				Mismatch {
+					index: 0,
+					expected: Some(ping(0)),
+					actual: quit(),
+				},
+				Mismatch {
+					index: 1,
+					expected: Some(ping(1)),
+					actual: ping(0),
+				},
+			]
+		);
+		assert!(client.mismatches().is_empty());
+	}
+	#[test]
+	fn write_error() {
+		struct Fail;
+		impl Write for Fail {
+			fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+				Err(io::ErrorKind::BrokenPipe.into())
+			}
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+		let mut recorder = Recorder::new(Fail);
+		recorder.request(&ping(0));
+		recorder.response(&pong(0, "a"));
+		assert_eq!(recorder.error().unwrap().kind(), io::ErrorKind::BrokenPipe);
+	}
+}
@@ -1,5 +1,7 @@
 use super::*;
-use sc2_prost::{RequestAction, RequestMapCommand, RequestObserverAction, RequestStep};
+use sc2_prost::{
+	ActionResult, RequestAction, RequestMapCommand, RequestObserverAction, RequestStep,
+};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GameInfo;
@@ -43,6 +45,11 @@ impl Observation {
 		self
 	}
 }
+impl From<sc2_prost::RequestObservation> for Observation {
+	fn from(r: sc2_prost::RequestObservation) -> Self {
+		Self(r)
+	}
+}
 impl From<Observation> for Request {
 	fn from(r: Observation) -> Self {
 		Self {
@@ -52,11 +59,11 @@ impl From<Observation> for Request {
 	}
 }
 impl ParseResponse for Observation {
-	type Output = Res<sc2_prost::ResponseObservation>;
+	type Output = Res<Obs>;
 
 	fn parse(res: Response) -> Result<Self::Output> {
 		convert_res(res, Kind::Observation)?.try_map(|res| match res {
-			ResponseVar::Observation(res) => Ok(res),
+			ResponseVar::Observation(res) => Ok(res.into()),
 			_ => Err(BadResError(Kind::Observation, res.kind()).into()),
 		})
 	}
@@ -73,6 +80,11 @@ pub fn action(actions: Vec<sc2_prost::Action>) -> Action {
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Action(RequestAction);
+impl Action {
+	pub fn actions(&self) -> &[sc2_prost::Action] {
+		&self.0.actions
+	}
+}
 impl From<Action> for Request {
 	fn from(r: Action) -> Self {
 		Self {
@@ -82,15 +94,66 @@ impl From<Action> for Request {
 	}
 }
 impl ParseResponse for Action {
-	type Output = Res<sc2_prost::ResponseAction>;
+	type Output = Res<ActionResults>;
 
 	fn parse(res: Response) -> Result<Self::Output> {
 		convert_res(res, Kind::Action)?.try_map(|res| match res {
-			ResponseVar::Action(res) => Ok(res),
+			ResponseVar::Action(res) => Ok(res.into()),
 			_ => Err(BadResError(Kind::Action, res.kind()).into()),
 		})
 	}
 }
+
+/**
+Results of [`Action`] request in the order actions were submitted.
+
+SC2 doesn't send actions back, so use [`zip`](Self::zip) to pair them with the submitted ones.
+
+# Examples
+```no_run
+use sc2_core::request::action;
+
+let req = action(actions);
+let submitted = req.actions().to_vec();
+let res = client.request(req)?;
+for (action, result) in res.data.zip(submitted) {
+	println!("{action:?}: {result:?}");
+}
+```
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ActionResults(pub Vec<ActionResult>);
+impl ActionResults {
+	/// Pairs results with the submitted actions
+	pub fn zip<T>(
+		self,
+		actions: impl IntoIterator<Item = T>,
+	) -> impl Iterator<Item = (T, ActionResult)> {
+		actions.into_iter().zip(self.0)
+	}
+	/// Indices and results of actions which didn't succeed
+	pub fn failed(&self) -> impl Iterator<Item = (usize, ActionResult)> + '_ {
+		self.0
+			.iter()
+			.copied()
+			.enumerate()
+			.filter(|(_, r)| *r != ActionResult::Success)
+	}
+	pub fn is_success(&self) -> bool {
+		self.failed().next().is_none()
+	}
+}
+impl From<sc2_prost::ResponseAction> for ActionResults {
+	/// Unknown result codes are converted to [`ActionResult::Error`]
+	fn from(res: sc2_prost::ResponseAction) -> Self {
+		Self(
+			res.result
+				.into_iter()
+				.map(|r| ActionResult::try_from(r).unwrap_or(ActionResult::Error))
+				.collect(),
+		)
+	}
+}
 impl KindOf for Action {
 	fn kind(&self) -> Kind {
 		Kind::Action
@@ -314,13 +377,7 @@ impl ParseResponse for MapCommand {
 				if res.error == 0 {
 					return Ok(());
 				}
-				Err(Sc2Error {
-					kind: Kind::MapCommand,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::MapCommand, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::MapCommand, res.kind()).into()),
 		})
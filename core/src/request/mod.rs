@@ -8,5 +8,8 @@ pub use setup::*;
 pub mod game;
 pub use game::*;
 
+pub mod obs;
+pub use obs::*;
+
 pub mod other;
 pub use other::*;
@@ -0,0 +1,118 @@
+use sc2_prost::{
+	ActionError, ActionRaw, Alert, ChatReceived, MapState, ObservationRaw, PlayerCommon,
+	PlayerResult, ResponseObservation, Score, Unit,
+};
+
+/**
+View over [`ResponseObservation`] returned by [`Observation`](super::Observation) request.
+
+Accessors return empty data for fields missing in the response,
+so there is no need to unwrap nested options.
+
+# Examples
+```no_run
+use sc2_core::request::observation;
+
+let obs = client.request(observation())?.data;
+println!("Loop {}: {} minerals", obs.game_loop(), obs.minerals());
+for unit in obs.units() {
+	println!("{} at {:?}", unit.tag, unit.pos);
+}
+```
+*/
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Obs(pub ResponseObservation);
+impl Obs {
+	pub fn game_loop(&self) -> u32 {
+		self.observation().map_or(0, |obs| obs.game_loop)
+	}
+
+	pub fn player_common(&self) -> Option<&PlayerCommon> {
+		self.observation()?.player_common.as_ref()
+	}
+	pub fn minerals(&self) -> u32 {
+		self.player_common().map_or(0, |p| p.minerals)
+	}
+	pub fn vespene(&self) -> u32 {
+		self.player_common().map_or(0, |p| p.vespene)
+	}
+	pub fn food_used(&self) -> u32 {
+		self.player_common().map_or(0, |p| p.food_used)
+	}
+	pub fn food_cap(&self) -> u32 {
+		self.player_common().map_or(0, |p| p.food_cap)
+	}
+	/// Supply left before hitting the cap
+	pub fn food_left(&self) -> u32 {
+		self.food_cap().saturating_sub(self.food_used())
+	}
+
+	/// Alerts raised since the last observation. Unknown ones are skipped
+	pub fn alerts(&self) -> impl Iterator<Item = Alert> + '_ {
+		self.observation()
+			.into_iter()
+			.flat_map(|obs| &obs.alerts)
+			.filter_map(|&alert| Alert::try_from(alert).ok())
+	}
+	/// Errors of actions made since the last observation
+	pub fn action_errors(&self) -> &[ActionError] {
+		&self.0.action_errors
+	}
+	/// Actions made since the last observation, including ones made by the user in UI
+	pub fn actions(&self) -> &[sc2_prost::Action] {
+		&self.0.actions
+	}
+	/// Raw actions made since the last observation paired with game loops they were made at
+	pub fn raw_actions(&self) -> impl Iterator<Item = (u32, &ActionRaw)> {
+		self.0
+			.actions
+			.iter()
+			.filter_map(|a| Some((a.game_loop, a.action_raw.as_ref()?)))
+	}
+	pub fn chat(&self) -> &[ChatReceived] {
+		&self.0.chat
+	}
+	/// Results of the players. Only present when the game has ended
+	pub fn player_results(&self) -> &[PlayerResult] {
+		&self.0.player_result
+	}
+
+	pub fn observation(&self) -> Option<&sc2_prost::Observation> {
+		self.0.observation.as_ref()
+	}
+	pub fn raw(&self) -> Option<&ObservationRaw> {
+		self.observation()?.raw_data.as_ref()
+	}
+	/// Units visible to the player. Requires raw interface
+	pub fn units(&self) -> &[Unit] {
+		self.raw().map_or(&[], |raw| &raw.units)
+	}
+	/// Creep and visibility. Requires raw interface
+	pub fn map_state(&self) -> Option<&MapState> {
+		self.raw()?.map_state.as_ref()
+	}
+	/// Tags of units which died since the last observation. Requires raw interface
+	pub fn dead_units(&self) -> &[u64] {
+		self.raw()
+			.and_then(|raw| raw.event.as_ref())
+			.map_or(&[], |event| &event.dead_units)
+	}
+	/// Requires score interface
+	pub fn score(&self) -> Option<&Score> {
+		self.observation()?.score.as_deref()
+	}
+
+	pub fn into_inner(self) -> ResponseObservation {
+		self.0
+	}
+}
+impl From<ResponseObservation> for Obs {
+	fn from(res: ResponseObservation) -> Self {
+		Self(res)
+	}
+}
+impl From<Obs> for ResponseObservation {
+	fn from(obs: Obs) -> Self {
+		obs.0
+	}
+}
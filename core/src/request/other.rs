@@ -16,6 +16,13 @@ impl ReplayInfo {
 		self.0.replay = Some(Replay::ReplayData(data));
 		self
 	}
+	/// Translates replay path for the game running under Wine, see [`to_windows_path`]
+	pub fn wine_paths(mut self) -> Self {
+		if let Some(Replay::ReplayPath(path)) = &mut self.0.replay {
+			*path = to_windows_path(path);
+		}
+		self
+	}
 
 	pub fn download_data(mut self, value: bool) -> Self {
 		self.0.download_data = value;
@@ -39,13 +46,7 @@ impl ParseResponse for ReplayInfo {
 				if res.error == 0 {
 					return Ok(res);
 				}
-				Err(Sc2Error {
-					kind: Kind::ReplayInfo,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::ReplayInfo, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::ReplayInfo, res.kind()).into()),
 		})
@@ -116,13 +117,7 @@ impl ParseResponse for SaveMap {
 				if res.error == 0 {
 					return Ok(());
 				}
-				Err(Sc2Error {
-					kind: Kind::SaveMap,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: String::new(),
-				}
-				.into())
+				Err(Sc2Error::new(Kind::SaveMap, res.error, String::new()).into())
 			}
 			_ => Err(BadResError(Kind::SaveMap, res.kind()).into()),
 		})
@@ -60,6 +60,14 @@ impl CreateGame {
 		self.0.realtime = value;
 		self
 	}
+
+	/// Translates local map path for the game running under Wine, see [`to_windows_path`]
+	pub fn wine_paths(mut self) -> Self {
+		if let Some(Map::LocalMap(LocalMap { map_path, .. })) = &mut self.0.map {
+			*map_path = to_windows_path(map_path);
+		}
+		self
+	}
 }
 impl From<CreateGame> for Request {
 	fn from(r: CreateGame) -> Self {
@@ -78,13 +86,7 @@ impl ParseResponse for CreateGame {
 				if res.error == 0 {
 					return Ok(());
 				}
-				Err(Sc2Error {
-					kind: Kind::CreateGame,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::CreateGame, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::CreateGame, res.kind()).into()),
 		})
@@ -212,13 +214,7 @@ impl ParseResponse for JoinGame {
 				if res.error == 0 {
 					return Ok(PlayerId(res.player_id));
 				}
-				Err(Sc2Error {
-					kind: Kind::JoinGame,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::JoinGame, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::JoinGame, res.kind()).into()),
 		})
@@ -296,6 +292,13 @@ impl StartReplay {
 		self.0.replay = Some(Replay::ReplayData(data));
 		self
 	}
+	/// Translates replay path for the game running under Wine, see [`to_windows_path`]
+	pub fn wine_paths(mut self) -> Self {
+		if let Some(Replay::ReplayPath(path)) = &mut self.0.replay {
+			*path = to_windows_path(path);
+		}
+		self
+	}
 
 	pub fn map_data(mut self, data: Vec<u8>) -> Self {
 		self.0.map_data = data;
@@ -340,13 +343,7 @@ impl ParseResponse for StartReplay {
 				if res.error == 0 {
 					return Ok(());
 				}
-				Err(Sc2Error {
-					kind: Kind::StartReplay,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::StartReplay, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::StartReplay, res.kind()).into()),
 		})
@@ -377,13 +374,7 @@ impl ParseResponse for RestartGame {
 				if res.error == 0 {
 					return Ok(res.need_hard_reset);
 				}
-				Err(Sc2Error {
-					kind: Kind::RestartGame,
-					code: res.error,
-					err: format!("{:?}", res.error()),
-					desc: res.error_details,
-				}
-				.into())
+				Err(Sc2Error::new(Kind::RestartGame, res.error, res.error_details).into())
 			}
 			_ => Err(BadResError(Kind::RestartGame, res.kind()).into()),
 		})
@@ -7,6 +7,7 @@ use std::{
 use thiserror::Error;
 use tungstenite::{
 	Error as WsError, HandshakeError, ServerHandshake,
+	error::ProtocolError,
 	handshake::server::{
 		Callback, ErrorResponse, Request as ServerRequest, Response as ServerResponse,
 	},
@@ -34,6 +35,19 @@ impl From<HsError> for Error {
 		Self::Handshake(Box::new(e))
 	}
 }
+impl Error {
+	/// Checks if the error means that client has disconnected
+	pub fn is_disconnect(&self) -> bool {
+		matches!(
+			self,
+			Self::WebSocket(
+				WsError::ConnectionClosed
+					| WsError::AlreadyClosed
+					| WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+			)
+		)
+	}
+}
 
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
 
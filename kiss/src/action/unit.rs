@@ -4,7 +4,8 @@ use std::fmt;
 use std::hash::Hash;
 
 use sc2_prost::{
-	Action, action_raw_unit_command::Target as PbTarget, unit_order::Target as PbOrderTarget,
+	Action, ActionResult, action_raw_unit_command::Target as PbTarget,
+	unit_order::Target as PbOrderTarget,
 };
 use sc2_prost::{Point, Point2D};
 
@@ -327,8 +328,80 @@ fn raw_units_action(
 	}
 }
 
-impl ToActions for UnitsActions<IVec2> {
-	fn to_iter(self) -> impl Iterator<Item = Action> {
+/// Units and action behind a single raw command emitted by [`UnitsActions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionSource<P> {
+	pub tags: Vec<Tag>,
+	pub action: UnitAction<P>,
+	pub queue: bool,
+}
+
+/// Raw commands emitted by [`UnitsActions`] together with their sources
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedActions<P> {
+	pub actions: Vec<Action>,
+	/// Source of each command in `actions`
+	pub sources: Vec<ActionSource<P>>,
+}
+impl<P> TrackedActions<P> {
+	/**
+	Maps results of the submitted commands back to units.
+
+	If multiple commands were sent for a unit, the first failed one is reported.
+	*/
+	pub fn results(
+		&self,
+		results: impl IntoIterator<Item = ActionResult>,
+	) -> HashMap<Tag, ActionResult> {
+		let mut unit_results = HashMap::<Tag, ActionResult>::new();
+		for (source, result) in self.sources.iter().zip(results) {
+			for tag in &source.tags {
+				let entry = unit_results.entry(*tag).or_insert(result);
+				if *entry == ActionResult::Success {
+					*entry = result;
+				}
+			}
+		}
+		unit_results
+	}
+}
+impl<P> FromIterator<(Action, ActionSource<P>)> for TrackedActions<P> {
+	fn from_iter<I>(iter: I) -> Self
+	where
+		I: IntoIterator<Item = (Action, ActionSource<P>)>,
+	{
+		let (actions, sources) = iter.into_iter().unzip();
+		Self { actions, sources }
+	}
+}
+
+fn tracked<P>(action: UnitAction<P>, tags: Vec<Tag>, queue: bool) -> (Action, ActionSource<P>)
+where
+	P: ToTargetPos + Copy,
+{
+	let raw = raw_units_action(
+		tags.iter().map(|&tag| tag.into()).collect(),
+		action.ability,
+		action.target.map(Into::into),
+		queue,
+	);
+	(
+		raw,
+		ActionSource {
+			tags,
+			action,
+			queue,
+		},
+	)
+}
+
+impl UnitsActions<IVec2> {
+	/// Same as [`to_actions`](ToActions::to_actions), but keeps source of each raw command
+	pub fn to_tracked(self) -> TrackedActions<IVec2> {
+		self.tracked_iter().collect()
+	}
+
+	fn tracked_iter(self) -> impl Iterator<Item = (Action, ActionSource<IVec2>)> {
 		type Actions = HashMap<UnitAction<IVec2>, HashSet<Tag>>;
 		let mut force_actions = Actions::default();
 		let mut queue_actions = Vec::<Actions>::default();
@@ -359,19 +432,22 @@ impl ToActions for UnitsActions<IVec2> {
 					.flatten()
 					.map(|(action, tags)| (action, tags, true)),
 			)
-			.map(|(action, tags, queue)| {
-				raw_units_action(
-					tags.into_iter().map(Into::into).collect(),
-					action.ability,
-					action.target.map(Into::into),
-					queue,
-				)
-			})
+			.map(|(action, tags, queue)| tracked(action, tags.into_iter().collect(), queue))
 	}
 }
-
-impl ToActions for UnitsActions<Vec2> {
+impl ToActions for UnitsActions<IVec2> {
 	fn to_iter(self) -> impl Iterator<Item = Action> {
+		self.tracked_iter().map(|(action, _)| action)
+	}
+}
+
+impl UnitsActions<Vec2> {
+	/// Same as [`to_actions`](ToActions::to_actions), but keeps source of each raw command
+	pub fn to_tracked(self) -> TrackedActions<Vec2> {
+		self.tracked_iter().collect()
+	}
+
+	fn tracked_iter(self) -> impl Iterator<Item = (Action, ActionSource<Vec2>)> {
 		type ActionsOptUnit = HashMap<(Ability, Option<Tag>), HashSet<Tag>>;
 		type ActionsPos = Vec<(Ability, Vec2, Tag)>;
 		type Actions = (ActionsOptUnit, ActionsPos);
@@ -410,29 +486,28 @@ impl ToActions for UnitsActions<Vec2> {
 			}
 		}
 
-		fn to_actions(actions: Actions, queue: bool) -> impl Iterator<Item = Action> {
+		fn to_actions(
+			actions: Actions,
+			queue: bool,
+		) -> impl Iterator<Item = (Action, ActionSource<Vec2>)> {
 			actions
 				.0
 				.into_iter()
-				.map(move |((ability, target), tags)| {
-					(
+				.map(|((ability, target), tags)| {
+					let action = UnitAction {
 						ability,
-						target.map(Into::into).map(PbTarget::UnitTag),
-						tags.into_iter().map(Into::into).collect(),
-						queue,
-					)
+						target: target.map(Target::Unit),
+					};
+					(action, tags.into_iter().collect())
 				})
-				.chain(actions.1.into_iter().map(move |(ability, pos, tag)| {
-					(
+				.chain(actions.1.into_iter().map(|(ability, pos, tag)| {
+					let action = UnitAction {
 						ability,
-						Some(PbTarget::WorldSpacePos(pos.into())),
-						vec![tag.into()],
-						queue,
-					)
+						target: Some(Target::Pos(pos)),
+					};
+					(action, vec![tag])
 				}))
-				.map(|(ability, target, tags, queue)| {
-					raw_units_action(tags, ability, target, queue)
-				})
+				.map(move |(action, tags)| tracked(action, tags, queue))
 		}
 
 		to_actions(force_actions, false).chain(
@@ -442,3 +517,8 @@ impl ToActions for UnitsActions<Vec2> {
 		)
 	}
 }
+impl ToActions for UnitsActions<Vec2> {
+	fn to_iter(self) -> impl Iterator<Item = Action> {
+		self.tracked_iter().map(|(action, _)| action)
+	}
+}
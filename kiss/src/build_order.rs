@@ -0,0 +1,278 @@
+/*!
+Build order extraction from replays.
+
+Build steps are taken from raw commands of the players,
+so they record what was ordered, even if the order was later cancelled.
+
+Steps can be exported with [`BuildOrder::to_csv`],
+or with [`BuildOrder::to_json`] if `serde` feature is enabled.
+
+# Examples
+```no_run
+use sc2_kiss::{build_order::BuildOrderExtractor, replay::ReplayRunner};
+
+let runner = ReplayRunner::new("replays/game.SC2Replay".into())?.step_size(8);
+let order = BuildOrderExtractor::default().extract(&mut client, &runner, [1.into(), 2.into()])?;
+for step in &order.steps {
+	println!("{:>6.1}s {} [{}] {}", step.secs(), step.player, step.supply, step.item);
+}
+order.to_csv(std::fs::File::create("build_order.csv")?)?;
+```
+*/
+
+use super::*;
+use ids::{Ability, AbilityRemap, UnitKind, Upgrade};
+use replay::{ReplayObserver, ReplayRunner};
+use sc2_core::{
+	Client, Result,
+	common::PlayerId,
+	request::{Obs, data},
+};
+use sc2_prost::{ActionRaw, Attribute, ResponseData, action_raw::Action as RawAction};
+use std::{collections::HashMap, fmt, io, ops::ControlFlow};
+
+/// Game loops per second on `Faster` game speed
+pub const LOOPS_PER_SEC: f32 = 22.4;
+
+/// What was started by a build step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildItem {
+	Unit(UnitKind),
+	Structure(UnitKind),
+	Upgrade(Upgrade),
+}
+impl BuildItem {
+	pub fn category(&self) -> &'static str {
+		match self {
+			Self::Unit(_) => "unit",
+			Self::Structure(_) => "structure",
+			Self::Upgrade(_) => "upgrade",
+		}
+	}
+	/// Raw id of unit type or upgrade
+	pub fn id(&self) -> u32 {
+		match *self {
+			Self::Unit(kind) | Self::Structure(kind) => kind.0,
+			Self::Upgrade(upgrade) => upgrade.into(),
+		}
+	}
+}
+impl fmt::Display for BuildItem {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Unit(kind) | Self::Structure(kind) => kind.fmt(f),
+			Self::Upgrade(upgrade) => upgrade.fmt(f),
+		}
+	}
+}
+
+/**
+Single step of a build order.
+
+It's a command issued by the player, one per command even if multiple producers were selected.
+The game could still reject it, e.g. for lack of resources or supply.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BuildStep {
+	/// Game loop the command was issued at
+	pub game_loop: u32,
+	pub player: PlayerId,
+	/**
+	Supply used by the player at the observation following the command.
+
+	Commands are reported with the observation made after them,
+	so it can already include supply of the started units.
+	Step with smaller [`step_size`](ReplayRunner::step_size) to make it more precise.
+	*/
+	pub supply: u32,
+	pub item: BuildItem,
+}
+impl BuildStep {
+	/// Game time in seconds
+	pub fn secs(&self) -> f32 {
+		self.game_loop as f32 / LOOPS_PER_SEC
+	}
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for BuildStep {
+	fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+
+		let mut st = s.serialize_struct("BuildStep", 7)?;
+		st.serialize_field("game_loop", &self.game_loop)?;
+		st.serialize_field("time", &self.secs())?;
+		st.serialize_field("player", &self.player.0)?;
+		st.serialize_field("supply", &self.supply)?;
+		st.serialize_field("category", self.item.category())?;
+		st.serialize_field("id", &self.item.id())?;
+		st.serialize_field("name", &self.item.to_string())?;
+		st.end()
+	}
+}
+
+/// Build steps of all players sorted by game loop
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BuildOrder {
+	pub steps: Vec<BuildStep>,
+}
+impl BuildOrder {
+	pub fn player(&self, player: PlayerId) -> impl Iterator<Item = &BuildStep> {
+		self.steps.iter().filter(move |s| s.player == player)
+	}
+
+	/// Writes steps as CSV with `game_loop,time,player,supply,category,id,name` columns
+	pub fn to_csv(&self, mut w: impl io::Write) -> io::Result<()> {
+		writeln!(w, "game_loop,time,player,supply,category,id,name")?;
+		for step in &self.steps {
+			writeln!(
+				w,
+				"{},{:.1},{},{},{},{},\"{}\"",
+				step.game_loop,
+				step.secs(),
+				step.player.0,
+				step.supply,
+				step.item.category(),
+				step.item.id(),
+				step.item.to_string().replace('"', "\"\""),
+			)?;
+		}
+		Ok(())
+	}
+	/// Writes steps as JSON array with the same fields as in [`to_csv`](Self::to_csv)
+	#[cfg(feature = "serde")]
+	pub fn to_json(&self, w: impl io::Write) -> serde_json::Result<()> {
+		serde_json::to_writer_pretty(w, &self.steps)
+	}
+}
+
+/**
+Replay observer turning raw commands into build steps.
+
+Steps are issued commands, not confirmed starts, see [`BuildStep`].
+
+Game data used to map abilities to units and upgrades is requested on the first step,
+unless the extractor is created [`with_data`](Self::with_data).
+*/
+#[derive(Debug, Default, Clone)]
+pub struct BuildOrderExtractor {
+	remap: AbilityRemap,
+	produces: HashMap<Ability, BuildItem>,
+	steps: Vec<BuildStep>,
+}
+impl BuildOrderExtractor {
+	pub fn with_data(data: &ResponseData) -> Self {
+		let remap = AbilityRemap::with_data(&data.abilities);
+		let structure = Attribute::Structure as i32;
+		let units = data
+			.units
+			.iter()
+			.filter(|u| u.available && u.ability_id != 0)
+			.map(|u| {
+				let kind = UnitKind(u.unit_id);
+				let item = if u.attributes.contains(&structure) {
+					BuildItem::Structure(kind)
+				} else {
+					BuildItem::Unit(kind)
+				};
+				(Ability(u.ability_id), item)
+			});
+		let upgrades = data.upgrades.iter().filter(|u| u.ability_id != 0).map(|u| {
+			(
+				Ability(u.ability_id),
+				BuildItem::Upgrade(u.upgrade_id.into()),
+			)
+		});
+
+		let mut produces: HashMap<_, _> = units.chain(upgrades).collect();
+		// general abilities used when specific ones aren't known,
+		// ones shared by different items (e.g. building any tech lab) are ambiguous and skipped
+		let mut general = HashMap::<Ability, Option<BuildItem>>::new();
+		for (&ability, &item) in &produces {
+			let entry = general.entry(remap.remap(ability)).or_insert(Some(item));
+			if *entry != Some(item) {
+				*entry = None;
+			}
+		}
+		for (ability, item) in general {
+			if let Some(item) = item {
+				produces.entry(ability).or_insert(item);
+			}
+		}
+		Self {
+			remap,
+			produces,
+			steps: vec![],
+		}
+	}
+
+	/// What the ability starts, if anything
+	pub fn item(&self, ability: Ability) -> Option<BuildItem> {
+		self.produces
+			.get(&ability)
+			.or_else(|| self.produces.get(&self.remap.remap(ability)))
+			.copied()
+	}
+	/// Steps extracted so far
+	pub fn steps(&self) -> &[BuildStep] {
+		&self.steps
+	}
+
+	/// Records a step if the action orders some unit or upgrade
+	pub fn record(&mut self, player: PlayerId, supply: u32, game_loop: u32, action: &ActionRaw) {
+		let Some(RawAction::UnitCommand(cmd)) = &action.action else {
+			return;
+		};
+		let Some(item) = self.item(Ability(cmd.ability_id)) else {
+			return;
+		};
+		// command doesn't tell how many of the selected producers could take it
+		self.steps.push(BuildStep {
+			game_loop,
+			player,
+			supply,
+			item,
+		});
+	}
+
+	/// Plays the replay for each of the players and collects their build orders
+	pub fn extract(
+		mut self,
+		client: &mut Client,
+		runner: &ReplayRunner,
+		players: impl IntoIterator<Item = PlayerId>,
+	) -> Result<BuildOrder> {
+		for player in players {
+			let _ = runner
+				.clone()
+				.observed_player(player)
+				.run(client, &mut self)?;
+		}
+		Ok(self.into())
+	}
+}
+impl ReplayObserver for BuildOrderExtractor {
+	type Break = ();
+
+	fn on_step(&mut self, client: &mut Client, obs: Obs) -> Result<ControlFlow<()>> {
+		if self.produces.is_empty() {
+			let res = client.request(data().abilities(true).units(true).upgrades(true))?;
+			let steps = std::mem::take(&mut self.steps);
+			*self = Self::with_data(&res.data);
+			self.steps = steps;
+		}
+		let player = obs
+			.player_common()
+			.map_or(PlayerId::NEUTRAL, |p| p.player_id.into());
+		for (game_loop, action) in obs.raw_actions() {
+			self.record(player, obs.food_used(), game_loop, action);
+		}
+		Ok(ControlFlow::Continue(()))
+	}
+}
+impl From<BuildOrderExtractor> for BuildOrder {
+	fn from(extractor: BuildOrderExtractor) -> Self {
+		let mut steps = extractor.steps;
+		steps.sort_by_key(|s| (s.game_loop, s.player.0));
+		Self { steps }
+	}
+}
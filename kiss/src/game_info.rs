@@ -0,0 +1,140 @@
+/*!
+Typed view over [`ResponseGameInfo`].
+
+# Examples
+```no_run
+use sc2_kiss::{game_info::GameInfo, sc2_core::request};
+
+let player_id = client.request(join_game)?.data;
+let info = GameInfo::new(client.request(request::GameInfo)?.data, player_id);
+println!("Playing on {} against {:?}", info.map_name, info.opponent_races().collect::<Vec<_>>());
+
+let obs = client.request(request::observation())?.data;
+let map = info.tile_map(&obs).expect("Raw interface is enabled");
+```
+*/
+
+use super::*;
+use linalg::{IVec2, Rect, Vec2};
+use sc2_core::{common::PlayerId, request::Obs};
+use sc2_prost::{
+	AiBuild, Difficulty, InterfaceOptions, PlayerType, Race, ResponseGameInfo, StartRaw,
+};
+
+/// Player participating in the game
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInfo {
+	pub id: PlayerId,
+	pub kind: PlayerType,
+	/// Race chosen in game setup, can be [`Race::Random`]
+	pub race_requested: Race,
+	/// Race the player actually plays. Known only for own player and in replays
+	pub race_actual: Option<Race>,
+	/// Only set for computer players
+	pub difficulty: Option<Difficulty>,
+	/// Only set for computer players
+	pub ai_build: Option<AiBuild>,
+	pub name: String,
+}
+impl PlayerInfo {
+	/// Actual race if known, requested one otherwise
+	pub fn race(&self) -> Race {
+		self.race_actual.unwrap_or(self.race_requested)
+	}
+	pub fn is_observer(&self) -> bool {
+		self.kind == PlayerType::Observer
+	}
+}
+impl From<sc2_prost::PlayerInfo> for PlayerInfo {
+	fn from(info: sc2_prost::PlayerInfo) -> Self {
+		Self {
+			id: info.player_id.into(),
+			kind: info.r#type(),
+			race_requested: info.race_requested(),
+			race_actual: Some(info.race_actual()).filter(|&r| r != Race::NoRace),
+			difficulty: Some(info.difficulty()).filter(|&d| d != Difficulty::Unset),
+			ai_build: Some(info.ai_build()).filter(|&b| b != AiBuild::Unset),
+			name: info.player_name,
+		}
+	}
+}
+
+/// Static information about the game and map
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameInfo {
+	pub map_name: String,
+	/// Path to the map file as it was passed to the game
+	pub local_map_path: String,
+	pub mod_names: Vec<String>,
+	/// Id of the player which joined the game
+	pub player_id: PlayerId,
+	pub players: Vec<PlayerInfo>,
+	pub map_size: IVec2,
+	/// Area where units can be. Everything outside of it is map border
+	pub playable_area: Rect,
+	/// Possible start locations of the opponents
+	pub start_locations: Vec<Vec2>,
+	/// Interface options the player joined with
+	pub options: Option<InterfaceOptions>,
+	start_raw: StartRaw,
+}
+impl GameInfo {
+	/// Creates game info for the player with the given id returned by [`JoinGame`](sc2_core::request::JoinGame)
+	pub fn new(res: ResponseGameInfo, player_id: PlayerId) -> Self {
+		let start_raw = res.start_raw.unwrap_or_default();
+		Self {
+			map_name: res.map_name,
+			local_map_path: res.local_map_path,
+			mod_names: res.mod_names,
+			player_id,
+			players: res.player_info.into_iter().map(Into::into).collect(),
+			map_size: start_raw.map_size.map_or(IVec2::ZERO, Into::into),
+			playable_area: start_raw.playable_area.map(Into::into).unwrap_or_default(),
+			start_locations: start_raw
+				.start_locations
+				.iter()
+				.map(|&p| p.into())
+				.collect(),
+			options: res.options,
+			start_raw,
+		}
+	}
+
+	/// Info of the player which joined the game
+	pub fn me(&self) -> Option<&PlayerInfo> {
+		self.player(self.player_id)
+	}
+	pub fn player(&self, id: PlayerId) -> Option<&PlayerInfo> {
+		self.players.iter().find(|p| p.id == id)
+	}
+	/// Other players except observers
+	pub fn opponents(&self) -> impl Iterator<Item = &PlayerInfo> {
+		self.players
+			.iter()
+			.filter(|p| p.id != self.player_id && !p.is_observer())
+	}
+	/// Races of the opponents. Random ones are reported as [`Race::Random`] until revealed
+	pub fn opponent_races(&self) -> impl Iterator<Item = Race> {
+		self.opponents().map(PlayerInfo::race)
+	}
+
+	/// Pathing, placement and terrain height grids
+	pub fn start_raw(&self) -> &StartRaw {
+		&self.start_raw
+	}
+	/**
+	Builds tile map from the static grids and current creep and visibility.
+
+	Returns `None` if the observation has no map state, i.e. raw interface is disabled.
+	*/
+	pub fn tile_map(&self, obs: &Obs) -> Option<map::TileMap> {
+		let map_state = obs.map_state()?.clone();
+		let mut map = map::TileMap::from_raw(self.start_raw.clone(), map_state);
+		for tile in map.iter_mut() {
+			if tile.vision.is_clear() {
+				tile.last_seen = obs.game_loop();
+			}
+		}
+		Some(map)
+	}
+}
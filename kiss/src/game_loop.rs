@@ -1,12 +1,21 @@
-use sc2_core::{Client, Result, Status};
-use sc2_prost::{RequestObservation as ReqObs, ResponseObservation as ResObs};
+use sc2_core::{
+	Client, Result, Transport,
+	request::{Obs, Observation, step},
+};
+use sc2_prost::{RequestObservation as ReqObs, Status};
 use std::ops::ControlFlow;
 
-pub trait GameLoop {
+/**
+Bot stepping through the game.
+
+Implementing it for any [`Transport`] instead of the default [`Client`]
+allows to run the bot against recorded games too.
+*/
+pub trait GameLoop<C: Transport = Client> {
 	type Break;
 
 	// `ControlFlow::Break` can be used here to break out of game loop early
-	fn on_step(&mut self, client: &mut Client, obs: ResObs) -> Result<ControlFlow<Self::Break>>;
+	fn on_step(&mut self, client: &mut C, obs: Obs) -> Result<ControlFlow<Self::Break>>;
 
 	fn req_obs(&self) -> ReqObs {
 		<_>::default()
@@ -15,17 +24,19 @@ pub trait GameLoop {
 		1
 	}
 
-	fn run_game_loop(&mut self, client: &mut Client) -> Result<ControlFlow<Self::Break, ResObs>> {
+	fn run_game_loop(&mut self, client: &mut C) -> Result<ControlFlow<Self::Break, Obs>> {
 		loop {
-			let res = client.observation(self.req_obs())?;
+			let res = client.request(Observation::from(self.req_obs()))?;
 			if res.status == Status::Ended {
 				break Ok(ControlFlow::Continue(res.data));
 			}
 			if let ControlFlow::Break(val) = self.on_step(client, res.data)? {
 				break Ok(ControlFlow::Break(val));
 			}
-			// todo: sometimes returns an error when game ends in realtime mode
-			client.step(self.step_size())?;
+			// game can end during `on_step` in realtime mode, then observation reports it
+			if client.status() != Some(Status::Ended) {
+				client.request(step(self.step_size()))?;
+			}
 		}
 	}
 }
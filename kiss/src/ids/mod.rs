@@ -15,3 +15,8 @@ pub use effect::*;
 
 mod remap;
 pub use remap::*;
+
+#[cfg(feature = "dump")]
+mod names;
+#[cfg(feature = "dump")]
+pub use names::*;
@@ -0,0 +1,40 @@
+use super::*;
+use sc2_core::common::IdNames;
+use std::fmt;
+
+/**
+Resolves ids in [`dump`](sc2_core::common::dump) output with `Display` of the id types.
+
+# Examples
+```no_run
+use sc2_kiss::{ids::Names, sc2_core::common::dump};
+
+println!("{}", dump(&response).json().names(&Names));
+```
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Names;
+
+/// Name of the id if it's known, i.e. `Display` differs from `Debug`
+fn known<T: fmt::Display + fmt::Debug>(id: T) -> Option<String> {
+	let name = id.to_string();
+	(name != format!("{id:?}")).then_some(name)
+}
+
+impl IdNames for Names {
+	fn unit(&self, id: u32) -> Option<String> {
+		known(UnitKind(id))
+	}
+	fn ability(&self, id: u32) -> Option<String> {
+		known(Ability(id))
+	}
+	fn upgrade(&self, id: u32) -> Option<String> {
+		known(Upgrade(id))
+	}
+	fn buff(&self, id: u32) -> Option<String> {
+		known(Buff(id))
+	}
+	fn effect(&self, id: u32) -> Option<String> {
+		known(Effect(id))
+	}
+}
@@ -12,7 +12,7 @@ Stupidly simple Rust framework for Starcraft 2 AI bots
 - [x] Id generation
 - [x] Parse ladder CLI args
 - [ ] Add CLI parser for local play
-- [ ] Simplify running games
+- [x] Simplify running games
 - [x] Debug commands
 - [ ] Filter units and extract data
 - [ ] Work with positions, vectors, distances
@@ -21,7 +21,7 @@ Stupidly simple Rust framework for Starcraft 2 AI bots
 - [ ] Unit actions
 	- [x] Action construction and storage
 	- [x] Batch actions with same ability and target
-	- [ ] Return action results
+	- [x] Return action results
 	- [x] Support queuing multiple actions at once for a single unit
 - [ ] Map
 	- [x] Easy access to map data at any position
@@ -46,6 +46,18 @@ pub mod ids;
 #[cfg(feature = "unit")]
 pub mod unit;
 
+#[cfg(feature = "obs")]
+pub mod obs;
+
+#[cfg(feature = "game-info")]
+pub mod game_info;
+
+#[cfg(feature = "replay")]
+pub mod replay;
+
+#[cfg(feature = "build-order")]
+pub mod build_order;
+
 #[cfg(feature = "action")]
 pub mod action;
 
@@ -58,6 +70,9 @@ pub mod linalg;
 #[cfg(feature = "game-loop")]
 pub mod game_loop;
 
+#[cfg(feature = "local-match")]
+pub mod local_match;
+
 #[cfg(feature = "debug")]
 pub mod debug;
 
@@ -1,2 +1,36 @@
 pub use glam::{self, IVec2, IVec3, Vec2, Vec3};
 pub use ordered_float::OrderedFloat;
+use sc2_prost::RectangleI;
+
+/// Rectangle of tiles from `min` (inclusive) to `max` (exclusive)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+	pub min: IVec2,
+	pub max: IVec2,
+}
+impl Rect {
+	pub fn new(min: IVec2, max: IVec2) -> Self {
+		Self { min, max }
+	}
+	pub fn size(&self) -> IVec2 {
+		self.max - self.min
+	}
+	pub fn center(&self) -> Vec2 {
+		(self.min + self.max).as_vec2() / 2.
+	}
+	pub fn contains(&self, pos: IVec2) -> bool {
+		pos.cmpge(self.min).all() && pos.cmplt(self.max).all()
+	}
+	/// Whether the point lies within the rectangle
+	pub fn contains_point(&self, pos: Vec2) -> bool {
+		pos.cmpge(self.min.as_vec2()).all() && pos.cmplt(self.max.as_vec2()).all()
+	}
+}
+impl From<RectangleI> for Rect {
+	fn from(rect: RectangleI) -> Self {
+		Self {
+			min: rect.p0.map_or(IVec2::ZERO, Into::into),
+			max: rect.p1.map_or(IVec2::ZERO, Into::into),
+		}
+	}
+}
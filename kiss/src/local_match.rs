@@ -0,0 +1,341 @@
+/*!
+Running local games between bots or against built-in AI.
+
+# Examples
+```no_run
+use sc2_kiss::{
+	local_match::{Match, bot},
+	sc2_core::request::computer,
+	sc2_prost::{Difficulty, Race},
+};
+
+let result = Match::new(
+	"Maps/AcropolisLE.SC2Map".into(),
+	bot(Race::Terran, my_bot),
+	computer().race(Race::Zerg).difficulty(Difficulty::Hard),
+)
+.realtime(false)
+.seed(42)
+.run()?;
+println!("{:?} after {} game loops", result.results, result.game_loops);
+```
+*/
+
+use crate::game_loop::GameLoop;
+use sc2_core::{
+	Client, Result as ClientResult,
+	launcher::{Error, GamePorts, InstancePool, LauncherBuilder, OnDrop, Pooled, Result, launcher},
+	request::{
+		Computer, CreateGame, LeaveGame, PARTICIPANT, SaveReplay, Utf8PathBuf, create_game,
+		interface, join_game, observation,
+	},
+};
+use sc2_prost::{InterfaceOptions, PlayerResult, PlayerSetup, Race, Status};
+use std::{fmt, panic, thread};
+
+type RunFn<'a> = Box<dyn FnOnce(&mut Client) -> ClientResult<()> + Send + 'a>;
+
+/// Bot participating in [`Match`]
+pub struct Bot<'a> {
+	race: Race,
+	name: String,
+	interface: InterfaceOptions,
+	run: RunFn<'a>,
+}
+impl fmt::Debug for Bot<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Bot")
+			.field("race", &self.race)
+			.field("name", &self.name)
+			.field("interface", &self.interface)
+			.finish_non_exhaustive()
+	}
+}
+impl<'a> Bot<'a> {
+	/// Bot which runs the given function after joining the game
+	pub fn new(race: Race, run: impl FnOnce(&mut Client) -> ClientResult<()> + Send + 'a) -> Self {
+		Self {
+			race,
+			name: String::new(),
+			interface: interface().raw(true).score(true).into(),
+			run: Box::new(run),
+		}
+	}
+	pub fn name(mut self, name: String) -> Self {
+		self.name = name;
+		self
+	}
+	/// Sets interface options to join the game with.
+	///
+	/// Defaults to raw interface with score.
+	pub fn interface(mut self, interface: impl Into<InterfaceOptions>) -> Self {
+		self.interface = interface.into();
+		self
+	}
+}
+
+/// Bot which runs the given game loop
+pub fn bot<'a>(race: Race, mut game_loop: impl GameLoop + Send + 'a) -> Bot<'a> {
+	Bot::new(race, move |client| {
+		let _ = game_loop.run_game_loop(client)?;
+		Ok(())
+	})
+}
+
+/// Second player in [`Match`]
+#[derive(Debug)]
+pub enum Participant<'a> {
+	Bot(Bot<'a>),
+	Computer(Computer),
+}
+impl<'a> From<Bot<'a>> for Participant<'a> {
+	fn from(bot: Bot<'a>) -> Self {
+		Self::Bot(bot)
+	}
+}
+impl From<Computer> for Participant<'_> {
+	fn from(computer: Computer) -> Self {
+		Self::Computer(computer)
+	}
+}
+
+/// Outcome of the finished [`Match`]
+#[derive(Debug, Default)]
+pub struct MatchResult {
+	/// Results of all players as reported to the bots.
+	///
+	/// Sorted by player id, which follows the order of participants.
+	pub results: Vec<PlayerResult>,
+	/// Game loop at which the game ended
+	pub game_loops: u32,
+	/// Replay of the game saved by the first bot
+	pub replay: Option<Vec<u8>>,
+	/// Error of returning instances to the pool after the game in [`Match::run_in`]
+	pub release_error: Option<Error>,
+}
+
+/// What single bot has seen after the game
+struct BotOutcome {
+	results: Vec<PlayerResult>,
+	game_loop: u32,
+	replay: Option<Vec<u8>>,
+}
+
+/**
+Local game between a bot and a second participant.
+
+Launches required SC2 instances, creates the game, joins it with all bots in parallel
+and runs them until the game ends. See [module docs](self) for example.
+*/
+#[derive(Debug)]
+pub struct Match<'a> {
+	map: Utf8PathBuf,
+	bot: Bot<'a>,
+	opponent: Participant<'a>,
+	realtime: bool,
+	seed: Option<u32>,
+	disable_fog: bool,
+	save_replay: bool,
+	launcher: LauncherBuilder<'a>,
+}
+impl<'a> Match<'a> {
+	pub fn new(map: Utf8PathBuf, bot: Bot<'a>, opponent: impl Into<Participant<'a>>) -> Self {
+		Self {
+			map,
+			bot,
+			opponent: opponent.into(),
+			realtime: false,
+			seed: None,
+			disable_fog: false,
+			save_replay: true,
+			launcher: launcher(),
+		}
+	}
+
+	pub fn realtime(mut self, value: bool) -> Self {
+		self.realtime = value;
+		self
+	}
+	pub fn seed(mut self, seed: u32) -> Self {
+		self.seed = Some(seed);
+		self
+	}
+	pub fn disable_fog(mut self, value: bool) -> Self {
+		self.disable_fog = value;
+		self
+	}
+	/// Whether to save replay after the game. Defaults to `true`
+	pub fn save_replay(mut self, value: bool) -> Self {
+		self.save_replay = value;
+		self
+	}
+	/// Sets launcher used to start SC2 instances. Address and drop behaviour are overridden.
+	pub fn launcher(mut self, launcher: LauncherBuilder<'a>) -> Self {
+		self.launcher = launcher;
+		self
+	}
+
+	/// Launches instances and plays the game. Instances are closed afterwards.
+	pub fn run(self) -> Result<MatchResult> {
+		let mut pool = InstancePool::new(self.launcher.clone().on_drop(OnDrop::Kill));
+		let mut instances = pool.acquire_many(self.bot_count())?;
+		// instances are killed on drop, releasing them would only relaunch the game
+		self.play(&mut pool, &mut instances)
+	}
+
+	/**
+	Plays the game on instances from the given pool and returns them back.
+
+	Failure to return instances doesn't discard the result of the played game,
+	it's reported in [`MatchResult::release_error`] instead.
+	*/
+	pub fn run_in(self, pool: &mut InstancePool) -> Result<MatchResult> {
+		let mut instances = pool.acquire_many(self.bot_count())?;
+		let result = self.play(pool, &mut instances);
+		let mut release_error = None;
+		for pooled in instances {
+			if let Err(e) = pool.release(pooled) {
+				release_error.get_or_insert(e);
+			}
+		}
+		let mut result = result?;
+		result.release_error = release_error;
+		Ok(result)
+	}
+
+	fn bot_count(&self) -> usize {
+		match self.opponent {
+			Participant::Bot(_) => 2,
+			Participant::Computer(_) => 1,
+		}
+	}
+
+	fn play(self, pool: &mut InstancePool, instances: &mut [Pooled]) -> Result<MatchResult> {
+		let mut bots = vec![self.bot];
+		let mut setup = vec![PlayerSetup {
+			race: bots[0].race as i32,
+			..PARTICIPANT
+		}];
+		match self.opponent {
+			Participant::Bot(bot) => {
+				setup.push(PlayerSetup {
+					race: bot.race as i32,
+					..PARTICIPANT
+				});
+				bots.push(bot);
+			}
+			Participant::Computer(computer) => setup.push(computer.into()),
+		}
+
+		let mut create = create_game()
+			.map(self.map)
+			.player_setup(setup)
+			.realtime(self.realtime)
+			.disable_fog(self.disable_fog);
+		if let Some(seed) = self.seed {
+			create = create.random_seed(seed);
+		}
+		if pool.runner().is_wine() {
+			create = create.wine_paths();
+		}
+
+		let ports = if bots.len() > 1 {
+			Some(pool.ports(bots.len())?)
+		} else {
+			None
+		};
+		let outcomes = play_game(instances, bots, create, ports.as_ref(), self.save_replay);
+		if let Some(ports) = ports {
+			pool.release_ports(ports);
+		}
+
+		let mut result = MatchResult::default();
+		for outcome in outcomes? {
+			let outcome = outcome?;
+			for player in outcome.results {
+				if !result
+					.results
+					.iter()
+					.any(|r| r.player_id == player.player_id)
+				{
+					result.results.push(player);
+				}
+			}
+			result.game_loops = result.game_loops.max(outcome.game_loop);
+			result.replay = result.replay.or(outcome.replay);
+		}
+		result.results.sort_by_key(|r| r.player_id);
+		Ok(result)
+	}
+}
+
+/// Creates the game and runs all bots in parallel until they finish
+fn play_game(
+	instances: &mut [Pooled],
+	bots: Vec<Bot>,
+	create: CreateGame,
+	ports: Option<&GamePorts>,
+	save_replay: bool,
+) -> ClientResult<Vec<ClientResult<BotOutcome>>> {
+	instances[0].client.request(create)?;
+
+	let outcomes = thread::scope(|s| {
+		let handles = instances
+			.iter_mut()
+			.zip(bots)
+			.enumerate()
+			.map(|(i, (pooled, bot))| {
+				let mut join = join_game()
+					.participant(bot.race)
+					.name(bot.name)
+					.interface(bot.interface);
+				if let Some(ports) = ports {
+					join = ports.apply(join);
+				}
+				let save_replay = save_replay && i == 0;
+				s.spawn(move || -> ClientResult<BotOutcome> {
+					let outcome = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+						let client = &mut pooled.client;
+						client.request(join)?;
+						(bot.run)(client)?;
+						finish(client, save_replay, ports.is_some())
+					}));
+					// other bots wait for this one on every step, so failed bot has to leave
+					if ports.is_some()
+						&& !matches!(outcome, Ok(Ok(_)))
+						&& pooled.client.request(LeaveGame).is_err()
+					{
+						let _ = pooled.instance.kill();
+					}
+					outcome.unwrap_or_else(|e| panic::resume_unwind(e))
+				})
+			})
+			.collect::<Vec<_>>();
+		handles
+			.into_iter()
+			.map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+			.collect()
+	});
+	Ok(outcomes)
+}
+
+/**
+Collects game outcome and leaves multiplayer game if the bot stopped before it ended.
+
+SC2 rejects leaving single player games, these are replaced by the next created game.
+*/
+fn finish(client: &mut Client, save_replay: bool, multiplayer: bool) -> ClientResult<BotOutcome> {
+	let res = client.request(observation())?;
+	let mut outcome = BotOutcome {
+		game_loop: res.data.game_loop(),
+		results: res.data.into_inner().player_result,
+		replay: None,
+	};
+	if save_replay {
+		outcome.replay = Some(client.request(SaveReplay)?.data);
+	}
+	if multiplayer && res.status != Status::Ended {
+		client.request(LeaveGame)?;
+	}
+	Ok(outcome)
+}
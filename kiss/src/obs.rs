@@ -0,0 +1,71 @@
+/*!
+Conversions of observation data into kiss types.
+
+# Examples
+```no_run
+use sc2_kiss::{obs::ObsExt, sc2_core::request::observation};
+
+let obs = client.request(observation())?.data;
+for tag in obs.dead_tags() {
+	println!("{tag} died");
+}
+```
+*/
+
+use super::*;
+use sc2_core::request::Obs;
+use sc2_prost::Alliance;
+use std::collections::HashMap;
+use unit::{RawUnit, Tag, UnitExt};
+
+/// Extension methods for [`Obs`]
+pub trait ObsExt {
+	/// Units with the given alliance relative to the player
+	fn units_of(&self, alliance: Alliance) -> impl Iterator<Item = &RawUnit>;
+	/// Units of the player
+	fn own_units(&self) -> impl Iterator<Item = &RawUnit> {
+		self.units_of(Alliance::Self_)
+	}
+	fn units_by_tag(&self) -> HashMap<Tag, &RawUnit>;
+	/// Tags of units which died since the last observation
+	fn dead_tags(&self) -> impl Iterator<Item = Tag>;
+	#[cfg(feature = "ids")]
+	fn units_of_kind(&self, kind: ids::UnitKind) -> impl Iterator<Item = &RawUnit>;
+	/// Upgrades researched by the player
+	#[cfg(feature = "ids")]
+	fn upgrades(&self) -> impl Iterator<Item = ids::Upgrade>;
+	/// Updates creep and visibility of the map. Requires raw interface
+	#[cfg(feature = "map")]
+	fn update_tile_map(&self, map: &mut map::TileMap);
+}
+impl ObsExt for Obs {
+	fn units_of(&self, alliance: Alliance) -> impl Iterator<Item = &RawUnit> {
+		self.units()
+			.iter()
+			.filter(move |u| u.alliance == alliance as i32)
+	}
+	fn units_by_tag(&self) -> HashMap<Tag, &RawUnit> {
+		self.units().iter().map(|u| (u.tag(), u)).collect()
+	}
+	fn dead_tags(&self) -> impl Iterator<Item = Tag> {
+		self.dead_units().iter().map(|&tag| tag.into())
+	}
+	#[cfg(feature = "ids")]
+	fn units_of_kind(&self, kind: ids::UnitKind) -> impl Iterator<Item = &RawUnit> {
+		self.units().iter().filter(move |u| u.kind() == kind)
+	}
+	#[cfg(feature = "ids")]
+	fn upgrades(&self) -> impl Iterator<Item = ids::Upgrade> {
+		self.raw()
+			.and_then(|raw| raw.player.as_ref())
+			.into_iter()
+			.flat_map(|player| &player.upgrade_ids)
+			.map(|&id| id.into())
+	}
+	#[cfg(feature = "map")]
+	fn update_tile_map(&self, map: &mut map::TileMap) {
+		if let Some(map_state) = self.map_state() {
+			map.update(map_state.clone(), self.game_loop());
+		}
+	}
+}
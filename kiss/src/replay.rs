@@ -0,0 +1,306 @@
+/*!
+Replay metadata and analysis.
+
+# Examples
+Reading metadata of all replays in a directory:
+```no_run
+use sc2_kiss::replay::scan_replays;
+
+let scan = scan_replays(&mut client, "replays".as_ref(), false)?;
+for (path, meta) in &scan.replays {
+	println!("{path}: {} ({:.0}s)", meta.map_name, meta.duration_secs);
+	for p in &meta.players {
+		println!("\t{:?} {:?} mmr {} apm {}", p.info.race(), p.result, p.mmr, p.apm);
+	}
+}
+for (path, e) in &scan.failed {
+	eprintln!("Skipped {path}: {e}");
+}
+```
+
+Collecting actions of every player:
+```no_run
+use sc2_kiss::replay::ReplayRunner;
+
+let runner = ReplayRunner::new("replays/game.SC2Replay".into())?.step_size(16);
+let actions = runner.player_actions(&mut client, meta.players.iter().map(|p| p.id()))?;
+for (player, actions) in actions {
+	println!("{player} made {} actions", actions.len());
+}
+```
+*/
+
+use super::*;
+use game_info::PlayerInfo;
+use game_loop::GameLoop;
+use sc2_core::{
+	Client, Error, Result,
+	common::{PlayerId, Sc2Error},
+	request::{Obs, StartReplay, Utf8PathBuf, interface, replay_info},
+};
+use sc2_prost::{
+	ActionRaw, InterfaceOptions, PlayerInfoExtra, RequestObservation as ReqObs, ResponseReplayInfo,
+	Result as GameResult,
+};
+use std::{collections::HashMap, fs, ops::ControlFlow, path::Path};
+
+/// Extension of replay files
+pub const REPLAY_EXT: &str = "SC2Replay";
+
+/// Player recorded in a replay
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReplayPlayer {
+	pub info: PlayerInfo,
+	/// Result of the game, `None` if it's not recorded
+	pub result: Option<GameResult>,
+	pub mmr: i32,
+	/// Actions per minute
+	pub apm: i32,
+}
+impl ReplayPlayer {
+	pub fn id(&self) -> PlayerId {
+		self.info.id
+	}
+}
+impl From<PlayerInfoExtra> for ReplayPlayer {
+	fn from(p: PlayerInfoExtra) -> Self {
+		Self {
+			info: p.player_info.unwrap_or_default().into(),
+			result: p
+				.player_result
+				.and_then(|r| GameResult::try_from(r.result).ok()),
+			mmr: p.player_mmr,
+			apm: p.player_apm,
+		}
+	}
+}
+
+/// Metadata of a replay returned by [`ReplayInfo`](sc2_core::request::ReplayInfo) request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayMeta {
+	pub map_name: String,
+	/// Path to the map file if it's available locally
+	pub local_map_path: String,
+	pub duration_loops: u32,
+	pub duration_secs: f32,
+	/// Game version string (e.g. `4.10.0.75689`)
+	pub game_version: String,
+	pub data_version: String,
+	pub data_build: u32,
+	/// Build of the game executable needed to run the replay
+	pub base_build: u32,
+	pub players: Vec<ReplayPlayer>,
+}
+impl ReplayMeta {
+	pub fn player(&self, id: PlayerId) -> Option<&ReplayPlayer> {
+		self.players.iter().find(|p| p.id() == id)
+	}
+	/// Players who won the game
+	pub fn winners(&self) -> impl Iterator<Item = &ReplayPlayer> {
+		self.players
+			.iter()
+			.filter(|p| p.result == Some(GameResult::Victory))
+	}
+}
+impl From<ResponseReplayInfo> for ReplayMeta {
+	fn from(res: ResponseReplayInfo) -> Self {
+		Self {
+			map_name: res.map_name,
+			local_map_path: res.local_map_path,
+			duration_loops: res.game_duration_loops,
+			duration_secs: res.game_duration_seconds,
+			game_version: res.game_version,
+			data_version: res.data_version,
+			data_build: res.data_build,
+			base_build: res.base_build,
+			players: res.player_info.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Result of [`scan_replays`]
+#[derive(Debug, Default)]
+pub struct ReplayScan {
+	pub replays: Vec<(Utf8PathBuf, ReplayMeta)>,
+	/// Replays which SC2 failed to read
+	pub failed: Vec<(Utf8PathBuf, Sc2Error)>,
+}
+
+/**
+Reads metadata of all replays in the directory, sorted by path.
+
+Paths are made absolute, since SC2 resolves relative ones against its own directory.
+Set `wine` if the game runs under Wine to [translate](sc2_core::common::to_windows_path) them.
+
+Replays rejected by SC2 are collected into [`ReplayScan::failed`],
+other errors like connection ones stop the scan.
+Files with non UTF-8 paths are skipped.
+*/
+pub fn scan_replays(client: &mut Client, dir: &Path, wine: bool) -> Result<ReplayScan> {
+	let mut paths = vec![];
+	for entry in fs::read_dir(dir.canonicalize()?)? {
+		let Ok(path) = Utf8PathBuf::try_from(entry?.path()) else {
+			continue;
+		};
+		if path.extension() == Some(REPLAY_EXT) {
+			paths.push(path);
+		}
+	}
+	paths.sort();
+
+	let mut scan = ReplayScan::default();
+	for path in paths {
+		let mut req = replay_info().replay(path.clone());
+		if wine {
+			req = req.wine_paths();
+		}
+		match client.request(req) {
+			Ok(res) => scan.replays.push((path, res.data.into())),
+			Err(Error::Sc2(e)) => scan.failed.push((path, e)),
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(scan)
+}
+
+/// Consumer of replay observations driven by [`ReplayRunner`]
+pub trait ReplayObserver {
+	type Break;
+
+	// `ControlFlow::Break` can be used here to stop watching replay early
+	fn on_step(&mut self, client: &mut Client, obs: Obs) -> Result<ControlFlow<Self::Break>>;
+
+	fn req_obs(&self) -> ReqObs {
+		<_>::default()
+	}
+}
+
+/// Raw action made by a player at the given game loop
+pub type TimedAction = (u32, ActionRaw);
+
+/// Collects raw actions of the observed player
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ActionCollector {
+	pub actions: Vec<TimedAction>,
+}
+impl ReplayObserver for ActionCollector {
+	type Break = ();
+
+	fn on_step(&mut self, _client: &mut Client, obs: Obs) -> Result<ControlFlow<()>> {
+		self.actions
+			.extend(obs.raw_actions().map(|(l, a)| (l, a.clone())));
+		Ok(ControlFlow::Continue(()))
+	}
+}
+
+/**
+Plays replay from the perspective of one player and feeds observations to [`ReplayObserver`].
+
+When created with [`ReplayRunner::new`], the first player is observed with raw interface and score.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayRunner {
+	request: StartReplay,
+	step_size: u32,
+}
+impl ReplayRunner {
+	/// Replay path is made absolute like in [`scan_replays`], so the file has to exist
+	pub fn new(replay: Utf8PathBuf) -> Result<Self> {
+		Ok(StartReplay::default()
+			.replay(replay.canonicalize_utf8()?)
+			.observed_player(PlayerId(1))
+			.interface(interface().raw(true).score(true))
+			.into())
+	}
+	pub fn observed_player(mut self, player: PlayerId) -> Self {
+		self.request = self.request.observed_player(player);
+		self
+	}
+	/// Number of game loops to step between observations. Defaults to `1`, `0` is treated as `1`
+	pub fn step_size(mut self, step_size: u32) -> Self {
+		self.step_size = step_size.max(1);
+		self
+	}
+	pub fn interface(mut self, interface: impl Into<InterfaceOptions>) -> Self {
+		self.request = self.request.interface(interface);
+		self
+	}
+	pub fn disable_fog(mut self, value: bool) -> Self {
+		self.request = self.request.disable_fog(value);
+		self
+	}
+	/// Translates replay path if the game runs under Wine
+	pub fn wine(mut self, value: bool) -> Self {
+		if value {
+			self.request = self.request.wine_paths();
+		}
+		self
+	}
+	/// Request used to start the replay
+	pub fn request(&self) -> &StartReplay {
+		&self.request
+	}
+
+	/**
+	Starts the replay and steps through it until the end or until observer breaks.
+
+	Returns the last observation if the replay has ended.
+	*/
+	pub fn run<O: ReplayObserver>(
+		&self,
+		client: &mut Client,
+		observer: &mut O,
+	) -> Result<ControlFlow<O::Break, Obs>> {
+		client.request(self.request.clone())?;
+		Observe {
+			observer,
+			step_size: self.step_size,
+		}
+		.run_game_loop(client)
+	}
+
+	/// Plays the replay once for every given player and collects their raw actions
+	pub fn player_actions(
+		&self,
+		client: &mut Client,
+		players: impl IntoIterator<Item = PlayerId>,
+	) -> Result<HashMap<PlayerId, Vec<TimedAction>>> {
+		let mut actions = HashMap::new();
+		for player in players {
+			let mut collector = ActionCollector::default();
+			let _ = self
+				.clone()
+				.observed_player(player)
+				.run(client, &mut collector)?;
+			actions.insert(player, collector.actions);
+		}
+		Ok(actions)
+	}
+}
+/// Drives observer with the same loop as bots
+struct Observe<'a, O> {
+	observer: &'a mut O,
+	step_size: u32,
+}
+impl<O: ReplayObserver> GameLoop for Observe<'_, O> {
+	type Break = O::Break;
+
+	fn on_step(&mut self, client: &mut Client, obs: Obs) -> Result<ControlFlow<O::Break>> {
+		self.observer.on_step(client, obs)
+	}
+	fn req_obs(&self) -> ReqObs {
+		self.observer.req_obs()
+	}
+	fn step_size(&self) -> u32 {
+		self.step_size
+	}
+}
+
+impl From<StartReplay> for ReplayRunner {
+	fn from(request: StartReplay) -> Self {
+		Self {
+			request,
+			step_size: 1,
+		}
+	}
+}
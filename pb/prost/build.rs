@@ -1,3 +1,9 @@
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use prost_build::Config;
+use prost_types::{
+	field_descriptor_proto::{Label, Type},
+	DescriptorProto, FileDescriptorSet,
+};
 use std::path::Path;
 
 fn main() {
@@ -7,30 +13,86 @@ fn main() {
 	let mut proto_file = protos_dir.to_owned();
 	proto_file.push("sc2api.proto");
 
-	let serde = "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]";
-	let serde_with = |path| format!("#[cfg_attr(feature = \"serde\", serde(with = \"{path}\"))]");
-	prost_build::Config::new()
+	let mut config = Config::new();
+	config
 		.boxed("Observation.feature_layer_data")
 		.boxed("Observation.score")
 		.boxed("Observation.ui_data")
-		.message_attribute("RequestCreateGame", "#[derive(Eq, Hash)]")
-		.message_attribute("ResponseData", serde)
-		.message_attribute("AbilityData", serde)
-		.enum_attribute("AbilityData.Target", serde)
-		.field_attribute("AbilityData.target", serde_with("srd::target"))
-		.message_attribute("UnitTypeData", serde)
-		.enum_attribute("Race", serde)
-		.field_attribute("UnitTypeData.race", serde_with("srd::race"))
-		.enum_attribute("Attribute", serde)
-		.field_attribute("UnitTypeData.attributes", serde_with("srd::attributes"))
-		.message_attribute("Weapon", serde)
-		.enum_attribute("Weapon.TargetType", serde)
-		.field_attribute("Weapon.target", serde_with("srd::weapon_target"))
-		.message_attribute("DamageBonus", serde)
-		.field_attribute("DamageBonus.attribute", serde_with("srd::attribute"))
-		.message_attribute("UpgradeData", serde)
-		.message_attribute("BuffData", serde)
-		.message_attribute("EffectData", serde)
-		.compile_protos(&[proto_file], &[protos_dir.parent().unwrap()])
-		.unwrap_or_else(|e| panic!("{e}"))
+		.message_attribute("RequestCreateGame", "#[derive(Eq, Hash)]");
+	let fds = config
+		.load_fds(&[proto_file], &[protos_dir.parent().unwrap()])
+		.unwrap_or_else(|e| panic!("{e}"));
+	serde_attributes(&mut config, &fds);
+	config.compile_fds(fds).unwrap_or_else(|e| panic!("{e}"))
+}
+
+fn serde(attr: &str) -> String {
+	format!("#[cfg_attr(feature = \"serde\", {attr})]")
+}
+
+/// Derives serde for all types.
+/// Enum fields are rendered as names of the values, oneofs as variants tagged with field names.
+fn serde_attributes(config: &mut Config, fds: &FileDescriptorSet) {
+	config
+		.type_attribute(".", serde("derive(serde::Serialize, serde::Deserialize)"))
+		.message_attribute(".", serde("serde(default)"));
+	for file in &fds.file {
+		let package = format!(".{}", file.package());
+		for message in &file.message_type {
+			serde_message(config, &package, file.package(), message);
+		}
+	}
+}
+fn serde_message(config: &mut Config, parent: &str, package: &str, message: &DescriptorProto) {
+	let path = format!("{parent}.{}", message.name());
+	for field in &message.field {
+		if field.r#type() != Type::Enum {
+			continue;
+		}
+		let helper = if field.label() == Label::Repeated {
+			"enums"
+		} else {
+			"enum"
+		};
+		let ty = rust_type(package, field.type_name());
+		let field_path = match field.oneof_index {
+			Some(i) => format!(
+				"{path}.{}.{}",
+				message.oneof_decl[i as usize].name(),
+				field.name()
+			),
+			None => format!("{path}.{}", field.name()),
+		};
+		config.field_attribute(
+			field_path,
+			serde(&format!(
+				"serde(serialize_with = \"crate::srd::ser_{helper}::<{ty}, _>\", \
+				deserialize_with = \"crate::srd::de_{helper}::<{ty}, _>\")"
+			)),
+		);
+	}
+	for oneof in &message.oneof_decl {
+		config.enum_attribute(
+			format!("{path}.{}", oneof.name()),
+			serde("serde(rename_all = \"snake_case\")"),
+		);
+	}
+	for nested in &message.nested_type {
+		serde_message(config, &path, package, nested);
+	}
+}
+/// Path of the generated Rust type for fully qualified proto type name
+fn rust_type(package: &str, type_name: &str) -> String {
+	let name = type_name
+		.trim_start_matches('.')
+		.trim_start_matches(package)
+		.trim_start_matches('.');
+	let mut parts: Vec<_> = name.split('.').collect();
+	let ty = parts.pop().unwrap().to_upper_camel_case();
+	let mut path = String::from("crate");
+	for module in parts {
+		path += "::";
+		path += &module.to_snake_case();
+	}
+	format!("{path}::{ty}")
 }
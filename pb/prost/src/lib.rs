@@ -1,44 +1,60 @@
 include!(concat!(env!("OUT_DIR"), "/sc2api_protocol.rs"));
 
+/// Helpers rendering enum fields as names of their values.
+/// Values unknown to this version of the protocol are kept as numbers
 #[cfg(feature = "serde")]
 mod srd {
-	use super::*;
 	use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-	macro_rules! serde_enums {
-		($($mod:ident => $enum:ty),+ $(,)?) => {$(
-			pub mod $mod {
-				use super::*;
-				pub fn serialize<S: Serializer>(num: &i32, s: S) -> Result<S::Ok, S::Error> {
-					<$enum>::try_from(*num)
-						.unwrap_or_default()
-						.serialize(s)
-				}
-				pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
-					<$enum>::deserialize(d).map(|res| res as i32)
-				}
-			}
-		)+};
+	#[derive(Serialize, Deserialize)]
+	#[serde(untagged)]
+	enum Named<E> {
+		Name(E),
+		Num(i32),
 	}
-	serde_enums! {
-		target => ability_data::Target,
-		race => Race,
-		attribute => Attribute,
-		weapon_target => weapon::TargetType,
-	}
-	pub mod attributes {
-		use super::*;
-		pub fn serialize<S: Serializer>(nums: &[i32], s: S) -> Result<S::Ok, S::Error> {
-			nums.iter()
-				.map(|num| Attribute::try_from(*num).unwrap_or_default())
-				.collect::<Vec<_>>()
-				.serialize(s)
+	impl<E: TryFrom<i32>> Named<E> {
+		fn new(num: i32) -> Self {
+			E::try_from(num).map_or(Self::Num(num), Self::Name)
 		}
-		pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<i32>, D::Error> {
-			Vec::<Attribute>::deserialize(d)
-				.map(|res| res.into_iter().map(|item| item as i32).collect())
+	}
+	impl<E: Into<i32>> Named<E> {
+		fn num(self) -> i32 {
+			match self {
+				Self::Name(e) => e.into(),
+				Self::Num(num) => num,
+			}
 		}
 	}
+
+	pub fn ser_enum<E, S>(num: &i32, s: S) -> Result<S::Ok, S::Error>
+	where
+		E: TryFrom<i32> + Serialize,
+		S: Serializer,
+	{
+		Named::<E>::new(*num).serialize(s)
+	}
+	pub fn de_enum<'de, E, D>(d: D) -> Result<i32, D::Error>
+	where
+		E: Into<i32> + Deserialize<'de>,
+		D: Deserializer<'de>,
+	{
+		Named::<E>::deserialize(d).map(Named::num)
+	}
+
+	pub fn ser_enums<E, S>(nums: &[i32], s: S) -> Result<S::Ok, S::Error>
+	where
+		E: TryFrom<i32> + Serialize,
+		S: Serializer,
+	{
+		s.collect_seq(nums.iter().map(|&num| Named::<E>::new(num)))
+	}
+	pub fn de_enums<'de, E, D>(d: D) -> Result<Vec<i32>, D::Error>
+	where
+		E: Into<i32> + Deserialize<'de>,
+		D: Deserializer<'de>,
+	{
+		Vec::<Named<E>>::deserialize(d).map(|res| res.into_iter().map(Named::num).collect())
+	}
 }
 
 #[cfg(feature = "glam")]
@@ -3,10 +3,11 @@ use bpaf::Bpaf;
 use camino::Utf8PathBuf;
 use convert_case::{Case, Casing};
 use sc2_core::{
-	launcher::{launcher, OnDrop::Kill},
+	client::Backoff,
+	launcher::{launcher, GameVersion, OnDrop::Kill},
 	request::{create_game, data, interface, join_game, PARTICIPANT},
 	sc2_prost::Race,
-	Client, Result,
+	Result,
 };
 use std::{
 	collections::HashSet,
@@ -58,16 +59,15 @@ fn gen(opts: Cli) -> Result {
 	let data = match opts.input {
 		Input::Map { map, addr } => {
 			// IMPORTANT: Instance must be binded to a variable so it doesn't immediately drop
-			let _instance = launcher()
+			let mut instance = launcher()
 				.addr(addr)
-				.version("Base75689")
-				.data_version("B89B5D6FA7CBF6452E721311BFBC6CB2")
+				.game_version(GameVersion::by_label("4.10.0").unwrap())
 				.on_drop(Kill)
 				.spawn()
 				.expect("Can't launch SC2");
 
-			let mut client = (0..3)
-				.find_map(|_| Client::connect(addr).ok())
+			let mut client = instance
+				.connect(&Backoff::default())
 				.expect("Can't connect");
 
 			client.request(create_game().map(map).player_setup(vec![PARTICIPANT]))?;